@@ -0,0 +1,263 @@
+use crate::model::Shift;
+use crate::tz::Zone;
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Effet d'une règle de disponibilité.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AvailabilityKind {
+    /// Contrainte dure : jamais sur ces plages.
+    Never,
+    /// Contrainte dure : uniquement sur ces plages.
+    Only,
+    /// Préférence : favorise ces plages.
+    Prefer,
+    /// Préférence : évite ces plages.
+    Avoid,
+}
+
+impl AvailabilityKind {
+    pub fn is_hard(&self) -> bool {
+        matches!(self, AvailabilityKind::Never | AvailabilityKind::Only)
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            AvailabilityKind::Never => "never",
+            AvailabilityKind::Only => "only",
+            AvailabilityKind::Prefer => "prefer",
+            AvailabilityKind::Avoid => "avoid",
+        }
+    }
+}
+
+/// Plage horaire locale ; si `end <= start`, elle se termine le lendemain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+/// Règle récurrente de disponibilité, évaluée en heure locale.
+///
+/// Syntaxe texte : `kind:[jours][@plage]`, par exemple `never:tue@night`
+/// (« jamais le mardi soir »), `only:weekend`, `prefer:@20:00-08:00`,
+/// `avoid:mon-fri@08:00-18:00`.
+/// Jours : `mon`..`sun`, intervalles `mon-fri`, listes `sat,sun`, `weekend`, `weekdays`.
+/// Plages : `HH:MM-HH:MM`, `night` (20:00-08:00) ou `day` (08:00-20:00).
+/// Une plage de nuit est rattachée au jour où elle commence.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct AvailabilityRule {
+    pub kind: AvailabilityKind,
+    /// Jours concernés ; vide = tous les jours.
+    pub days: Vec<Weekday>,
+    /// Plage horaire ; absente = journée entière.
+    pub window: Option<TimeWindow>,
+}
+
+impl AvailabilityRule {
+    /// Intervalles UTC où la règle s'applique, autour de [start, end).
+    fn windows(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        zone: Zone,
+    ) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let first = zone.naive_local(start).date() - Duration::days(1);
+        let last = zone.naive_local(end).date();
+        let midnight = NaiveTime::MIN;
+        let mut out = Vec::new();
+        for day in first.iter_days().take_while(|d| *d <= last) {
+            if !self.days.is_empty() && !self.days.contains(&day.weekday()) {
+                continue;
+            }
+            let (ws, we) = self
+                .window
+                .map_or((midnight, midnight), |w| (w.start, w.end));
+            let end_day = if we <= ws {
+                day + Duration::days(1)
+            } else {
+                day
+            };
+            if let (Some(a), Some(b)) = (
+                zone.from_local(day.and_time(ws)),
+                zone.from_local(end_day.and_time(we)),
+            ) {
+                out.push((a, b));
+            }
+        }
+        out
+    }
+
+    /// Minutes du shift couvertes par la règle.
+    pub fn overlap_minutes(&self, shift: &Shift, zone: Zone) -> i64 {
+        self.windows(shift.start, shift.end, zone)
+            .into_iter()
+            .map(|(a, b)| {
+                let s = shift.start.max(a);
+                let e = shift.end.min(b);
+                if e > s {
+                    (e - s).num_minutes()
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+
+    /// Vrai si une règle dure laisse passer le shift (toujours vrai pour une préférence).
+    pub fn allows(&self, shift: &Shift, zone: Zone) -> bool {
+        match self.kind {
+            AvailabilityKind::Never => self.overlap_minutes(shift, zone) == 0,
+            AvailabilityKind::Only => self.overlap_minutes(shift, zone) >= shift.duration_minutes(),
+            AvailabilityKind::Prefer | AvailabilityKind::Avoid => true,
+        }
+    }
+
+    /// Score de préférence en millièmes de la durée du shift
+    /// (positif pour `prefer`, négatif pour `avoid`, nul pour une règle dure).
+    pub fn score(&self, shift: &Shift, zone: Zone) -> i64 {
+        let sign = match self.kind {
+            AvailabilityKind::Prefer => 1,
+            AvailabilityKind::Avoid => -1,
+            AvailabilityKind::Never | AvailabilityKind::Only => return 0,
+        };
+        let duration = shift.duration_minutes().max(1);
+        sign * self.overlap_minutes(shift, zone) * 1000 / duration
+    }
+}
+
+const DAYS: [(&str, Weekday); 7] = [
+    ("mon", Weekday::Mon),
+    ("tue", Weekday::Tue),
+    ("wed", Weekday::Wed),
+    ("thu", Weekday::Thu),
+    ("fri", Weekday::Fri),
+    ("sat", Weekday::Sat),
+    ("sun", Weekday::Sun),
+];
+
+fn parse_day(s: &str) -> Result<Weekday, String> {
+    DAYS.iter()
+        .find(|(name, _)| *name == s)
+        .map(|(_, d)| *d)
+        .ok_or_else(|| format!("unknown day: {s}"))
+}
+
+fn day_name(d: Weekday) -> &'static str {
+    DAYS[d.num_days_from_monday() as usize].0
+}
+
+fn parse_days(raw: &str) -> Result<Vec<Weekday>, String> {
+    let mut out = Vec::new();
+    for token in raw.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        match token {
+            "weekend" => out.extend([Weekday::Sat, Weekday::Sun]),
+            "weekdays" => out.extend(DAYS[..5].iter().map(|(_, d)| *d)),
+            _ => {
+                if let Some((a, b)) = token.split_once('-') {
+                    let mut day = parse_day(a)?;
+                    let last = parse_day(b)?;
+                    out.push(day);
+                    while day != last {
+                        day = day.succ();
+                        out.push(day);
+                    }
+                } else {
+                    out.push(parse_day(token)?);
+                }
+            }
+        }
+    }
+    out.dedup();
+    Ok(out)
+}
+
+fn parse_window(raw: &str) -> Result<TimeWindow, String> {
+    let hm = |h, m| NaiveTime::from_hms_opt(h, m, 0).expect("valid time");
+    match raw {
+        "night" => {
+            return Ok(TimeWindow {
+                start: hm(20, 0),
+                end: hm(8, 0),
+            })
+        }
+        "day" => {
+            return Ok(TimeWindow {
+                start: hm(8, 0),
+                end: hm(20, 0),
+            })
+        }
+        _ => {}
+    }
+    let (a, b) = raw
+        .split_once('-')
+        .ok_or_else(|| format!("invalid time window: {raw}"))?;
+    let parse = |t: &str| {
+        NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|_| format!("invalid time: {t}"))
+    };
+    let window = TimeWindow {
+        start: parse(a)?,
+        end: parse(b)?,
+    };
+    if window.start == window.end {
+        return Err(format!("empty time window: {raw}"));
+    }
+    Ok(window)
+}
+
+impl FromStr for AvailabilityRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_ascii_lowercase();
+        let (kind, spec) = s
+            .split_once(':')
+            .ok_or_else(|| format!("invalid availability rule (expected kind:spec): {s}"))?;
+        let kind = match kind.trim() {
+            "never" => AvailabilityKind::Never,
+            "only" => AvailabilityKind::Only,
+            "prefer" => AvailabilityKind::Prefer,
+            "avoid" => AvailabilityKind::Avoid,
+            other => return Err(format!("unknown availability kind: {other}")),
+        };
+        let (days, window) = match spec.split_once('@') {
+            Some((days, window)) => (days, Some(parse_window(window.trim())?)),
+            None => (spec, None),
+        };
+        let days = parse_days(days)?;
+        if days.is_empty() && window.is_none() {
+            return Err(format!("availability rule matches nothing: {s}"));
+        }
+        Ok(Self { kind, days, window })
+    }
+}
+
+impl fmt::Display for AvailabilityRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.kind.as_str())?;
+        let days: Vec<&str> = self.days.iter().map(|d| day_name(*d)).collect();
+        f.write_str(&days.join(","))?;
+        if let Some(w) = self.window {
+            write!(f, "@{}-{}", w.start.format("%H:%M"), w.end.format("%H:%M"))?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for AvailabilityRule {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<AvailabilityRule> for String {
+    fn from(rule: AvailabilityRule) -> Self {
+        rule.to_string()
+    }
+}
@@ -1,14 +1,24 @@
 #![forbid(unsafe_code)]
 use anyhow::{bail, Result};
 use astreinte::{
+    availability::AvailabilityRule,
+    escalation::{self, EscalationStep},
+    holiday::HolidayCalendar,
     io,
-    model::{Person, ShiftId},
+    model::{
+        EscalationLevel, EscalationPolicy, EscalationTarget, FollowTheSun, Person, PersonId,
+        Region, Role, Roster, ShiftId, SwapRequestId, SwapStatus, Team,
+    },
     notification::{prepare_reminder, TextReminder},
-    scheduler::{AssignOptions, ConflictKind, Scheduler},
+    payroll::{self, PayrollOptions, RateTable},
+    query::{self, OutputFormat, ShiftFilter, ShiftQuery, SortKey},
+    scheduler::{AssignOptions, AssignScope, CoverageIssue, Scheduler},
+    stats::{self, Period, StatsOptions},
     storage::{JsonStorage, Storage},
+    tz::Zone,
 };
-use chrono::Utc;
-use clap::{Parser, Subcommand};
+use chrono::{DateTime, Datelike, NaiveTime, Utc};
+use clap::{ArgGroup, Args, Parser, Subcommand};
 #[cfg(feature = "logging")]
 use tracing_subscriber::{fmt::Subscriber, EnvFilter};
 
@@ -28,6 +38,261 @@ struct Cli {
     cmd: Commands,
 }
 
+/// Calendrier de fériés et fuseau de référence
+#[derive(Args, Debug)]
+struct HolidayArgs {
+    /// Fuseau pour jours/nuits/week-ends/fériés: utc, local ou nom IANA
+    /// (défaut : fuseau enregistré dans le roster)
+    #[arg(long)]
+    tz: Option<String>,
+    /// Inclure les jours fériés français (calculés, Pâques comprise)
+    #[arg(long)]
+    holidays_fr: bool,
+    /// Fichier de jours fériés (`YYYY-MM-DD[,nom]` par ligne)
+    #[arg(long)]
+    holidays: Option<String>,
+}
+
+impl HolidayArgs {
+    fn resolve(&self, roster: &Roster) -> Result<(HolidayCalendar, Zone)> {
+        let zone = match &self.tz {
+            Some(tz) => tz.parse().map_err(anyhow::Error::msg)?,
+            None => roster.zone,
+        };
+        let mut calendar = HolidayCalendar::new();
+        if self.holidays_fr {
+            let years = roster
+                .shifts
+                .iter()
+                .flat_map(|s| [s.start.year(), s.end.year()]);
+            let (min, max) = years.fold((i32::MAX, i32::MIN), |(lo, hi), y| (lo.min(y), hi.max(y)));
+            if min <= max {
+                // année précédente incluse pour la règle de rotation des fériés
+                calendar.add_french_years(min - 1..=max);
+            }
+        }
+        if let Some(path) = &self.holidays {
+            calendar.merge(HolidayCalendar::load(path)?);
+        }
+        Ok((calendar, zone))
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum SwapRequestCmd {
+    /// Proposer un échange (1 pour 1, 1 pour N, ou don sans `--take`)
+    Propose {
+        /// Personne qui cède ses shifts
+        #[arg(long)]
+        from: String,
+        /// Personne sollicitée
+        #[arg(long)]
+        to: String,
+        /// Shifts cédés par `--from` (ids séparés par des virgules)
+        #[arg(long, value_delimiter = ',', required = true)]
+        give: Vec<String>,
+        /// Shifts de `--to` demandés en retour
+        #[arg(long, value_delimiter = ',')]
+        take: Vec<String>,
+        /// Échéance de la proposition (RFC3339 ou YYYY-MM-DD inclus)
+        #[arg(long)]
+        expires: Option<String>,
+    },
+    /// Accepter une proposition (échange complet ou rien)
+    Accept {
+        #[arg(long)]
+        id: String,
+        #[arg(long, default_value_t = 11)]
+        min_rest_hours: u32,
+        #[arg(long, default_value_t = 3)]
+        max_consecutive_shifts: u32,
+    },
+    /// Refuser une proposition
+    Reject {
+        #[arg(long)]
+        id: String,
+    },
+    /// Lister les propositions (en attente par défaut)
+    List {
+        #[arg(long)]
+        handle: Option<String>,
+        /// Inclure les propositions closes
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum TeamCmd {
+    /// Créer une équipe et ses options de rotation
+    Add {
+        #[arg(long)]
+        name: String,
+        /// Membres "handle1,handle2,..." dans l'ordre de rotation
+        #[arg(long, value_delimiter = ',')]
+        members: Vec<String>,
+        #[arg(long, default_value_t = 11)]
+        min_rest_hours: u32,
+        #[arg(long, default_value_t = 3)]
+        max_consecutive_shifts: u32,
+        #[arg(long)]
+        balance_holidays: bool,
+        #[arg(long)]
+        holiday_rotation: bool,
+    },
+    /// Ajouter un membre en fin de rotation
+    AddMember {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        handle: String,
+    },
+    /// Retirer un membre (ses shifts restent assignés)
+    RemoveMember {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        handle: String,
+    },
+    /// Définir la chaîne d'escalade des alertes de l'équipe
+    Escalation {
+        #[arg(long)]
+        name: String,
+        /// Niveau `cible[:minutes]` dans l'ordre d'appel (répétable) : un rôle
+        /// d'astreinte (`primary`, `secondary`...) ou `@handle` ; les minutes
+        /// sans acquittement avant le niveau suivant (0 par défaut)
+        #[arg(long = "level", required_unless_present = "clear")]
+        levels: Vec<String>,
+        /// Retirer la chaîne d'escalade
+        #[arg(long, conflicts_with = "levels")]
+        clear: bool,
+    },
+    /// Lister les équipes
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+enum FollowTheSunCmd {
+    /// Déclarer une rotation et ses régions, dans l'ordre des passations
+    Add {
+        #[arg(long)]
+        name: String,
+        /// Région `equipe:fuseau@HH:MM-HH:MM` (répétable), ex. `eu:Europe/Paris@08:00-16:00`
+        #[arg(long = "region", required = true)]
+        regions: Vec<String>,
+    },
+    /// Générer et assigner les shifts régionaux, puis contrôler la couverture
+    Generate {
+        #[arg(long)]
+        name: String,
+        /// Premier jour (YYYY-MM-DD)
+        #[arg(long)]
+        from: String,
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+    },
+    /// Contrôler trous et chevauchements entre passations
+    Check {
+        #[arg(long)]
+        name: String,
+        /// Début (RFC3339 ou YYYY-MM-DD)
+        #[arg(long)]
+        from: String,
+        /// Fin (RFC3339 ou YYYY-MM-DD inclus)
+        #[arg(long)]
+        until: String,
+    },
+    /// Lister les rotations
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+enum OverrideCmd {
+    /// Faire tenir une portion d'un shift par quelqu'un d'autre, sans le découper
+    Add {
+        #[arg(long)]
+        shift_id: String,
+        /// Début du remplacement (RFC3339)
+        #[arg(long)]
+        from: String,
+        /// Fin du remplacement (RFC3339, exclue)
+        #[arg(long)]
+        until: String,
+        #[arg(long)]
+        with: String,
+        #[arg(long, default_value_t = 11)]
+        min_rest_hours: u32,
+        #[arg(long, default_value_t = 3)]
+        max_consecutive_shifts: u32,
+    },
+    /// Supprimer un remplacement par son index (voir `list`)
+    Remove {
+        #[arg(long)]
+        shift_id: String,
+        #[arg(long)]
+        index: usize,
+    },
+    /// Lister les remplacements
+    List {
+        #[arg(long)]
+        shift_id: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AvailabilityCmd {
+    /// Ajouter une règle (`never:tue@night`, `only:weekend`, `prefer:@night`...)
+    Add {
+        #[arg(long)]
+        handle: String,
+        #[arg(long)]
+        rule: String,
+    },
+    /// Supprimer une règle par son index (voir `list`)
+    Remove {
+        #[arg(long)]
+        handle: String,
+        #[arg(long)]
+        index: usize,
+    },
+    /// Lister les règles
+    List {
+        #[arg(long)]
+        handle: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum VacationCmd {
+    /// Déclarer des congés (YYYY-MM-DD ou RFC3339 ; date de fin incluse)
+    Add {
+        #[arg(long)]
+        handle: String,
+        #[arg(long)]
+        from: String,
+        /// Sans `--until`, seule la journée de `--from` est bloquée
+        #[arg(long)]
+        until: Option<String>,
+        /// Lister ensuite les shifts déjà assignés en conflit
+        #[arg(long)]
+        check: bool,
+        #[arg(long, default_value_t = 11)]
+        min_rest_hours: u32,
+    },
+    /// Supprimer une période par son index (voir `list`)
+    Remove {
+        #[arg(long)]
+        handle: String,
+        #[arg(long)]
+        index: usize,
+    },
+    /// Lister les congés
+    List {
+        #[arg(long)]
+        handle: Option<String>,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Créer un shift
@@ -40,18 +305,94 @@ enum Commands {
         /// RFC3339 UTC
         #[arg(long)]
         end: String,
+        /// Rôle (`primary`, `secondary` ou libre)
+        #[arg(long)]
+        role: Option<String>,
+        /// Équipe propriétaire du shift
+        #[arg(long)]
+        team: Option<String>,
     },
 
-    /// Importer des personnes depuis un CSV
+    /// Importer des personnes depuis un CSV (mise à jour par handle)
     ImportPeople {
+        /// Fichier CSV, ou classeur `.xlsx`/`.ods` avec la feature `spreadsheet`
         #[arg(long)]
         csv: String,
+        /// Afficher les changements sans enregistrer
+        #[arg(long)]
+        dry_run: bool,
+        /// Désactiver les membres absents du CSV
+        #[arg(long)]
+        prune: bool,
+        /// Importer les lignes valides et ignorer les autres (sinon rien n'est importé)
+        #[arg(long)]
+        skip_invalid: bool,
+    },
+
+    /// Importer un planning exporté de PagerDuty ou Opsgenie (fichier JSON)
+    ImportSchedule {
+        /// `pagerduty` ou `opsgenie`
+        #[arg(long)]
+        format: String,
+        #[arg(long)]
+        file: String,
+    },
+
+    /// Exporter le planning au format PagerDuty ou Opsgenie (JSON)
+    ExportSchedule {
+        /// `pagerduty` ou `opsgenie`
+        #[arg(long)]
+        format: String,
+        #[arg(long)]
+        out: String,
+        /// Nom du planning exporté
+        #[arg(long, default_value = "astreinte")]
+        name: String,
+    },
+
+    /// Exporter la grille de planning (un jour par ligne, un rôle par colonne) en XLSX
+    #[cfg(feature = "spreadsheet")]
+    ExportPlanning {
+        #[arg(long)]
+        out: String,
+        /// Premier jour (YYYY-MM-DD)
+        #[arg(long)]
+        from: String,
+        #[arg(long, default_value_t = 28)]
+        days: u32,
+        /// Restreindre aux shifts d'une équipe
+        #[arg(long)]
+        team: Option<String>,
+        #[command(flatten)]
+        holidays: HolidayArgs,
     },
 
     /// Importer des shifts depuis un CSV
     ImportShifts {
+        /// En-tête `name,start,end[,id][,role][,assigned_handle][,timezone]`,
+        /// ou fichier produit par `list --out-csv` (mise à jour par id) ;
+        /// classeur `.xlsx`/`.ods` accepté avec la feature `spreadsheet`
         #[arg(long)]
         csv: String,
+        /// Afficher les changements sans enregistrer
+        #[arg(long)]
+        dry_run: bool,
+        /// Équipe propriétaire des shifts importés
+        #[arg(long)]
+        team: Option<String>,
+        /// Séparateur de champs (`;` pour Excel en français)
+        #[arg(long, default_value_t = ',')]
+        delimiter: char,
+        /// Fuseau des dates sans décalage, sauf colonne `timezone`: utc, local ou nom IANA
+        #[arg(long, default_value = "utc")]
+        tz: String,
+        /// En-tête du fichier pour une colonne, `colonne=En-tête` (répétable),
+        /// ex. `--map start=Début`
+        #[arg(long = "map")]
+        columns: Vec<String>,
+        /// Importer les lignes valides et ignorer les autres (sinon rien n'est importé)
+        #[arg(long)]
+        skip_invalid: bool,
     },
 
     /// Assigner en round-robin
@@ -63,14 +404,66 @@ enum Commands {
         min_rest_hours: u32,
         #[arg(long, default_value_t = 3)]
         max_consecutive_shifts: u32,
+        /// Sur les fériés, privilégier qui en a le moins
+        #[arg(long)]
+        balance_holidays: bool,
+        /// Interdire le même férié deux années de suite
+        #[arg(long)]
+        holiday_rotation: bool,
+        #[command(flatten)]
+        holidays: HolidayArgs,
+        /// Mode incrémental : ne remplir que les shifts sans assignation
+        #[arg(long)]
+        only_unassigned: bool,
+        /// Mode incrémental : shifts se terminant après (RFC3339 ou YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Mode incrémental : shifts commençant avant (RFC3339 ou YYYY-MM-DD inclus)
+        #[arg(long)]
+        until: Option<String>,
+        /// N'assigner que les shifts de cette équipe, parmi ses membres et avec
+        /// ses options (`--people` et les options de rotation sont ignorés)
+        #[arg(long)]
+        team: Option<String>,
     },
 
-    /// Lister et optionnellement exporter
+    /// Lister (filtres, tri, formats) et optionnellement exporter
     List {
         #[arg(long)]
         out_json: Option<String>,
         #[arg(long)]
         out_csv: Option<String>,
+        /// Ajouter à `--out-csv` une ligne `override` par remplacement ponctuel
+        #[arg(long, requires = "out_csv")]
+        with_overrides: bool,
+        /// Shifts assignés à ce handle
+        #[arg(long)]
+        handle: Option<String>,
+        /// Shifts se terminant après (RFC3339 ou YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Shifts commençant avant (RFC3339 ou YYYY-MM-DD inclus)
+        #[arg(long)]
+        until: Option<String>,
+        /// Uniquement les shifts non assignés
+        #[arg(long)]
+        unassigned: bool,
+        #[arg(long)]
+        role: Option<String>,
+        /// Sous-chaîne du nom du shift
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        team: Option<String>,
+        /// Tri: start, end, name, handle (ordre du fichier par défaut)
+        #[arg(long)]
+        sort: Option<String>,
+        /// Format: table, json, csv, markdown
+        #[arg(long, default_value = "table")]
+        format: String,
+        /// Fuseau d'affichage: utc, local ou nom IANA (Europe/Paris)
+        #[arg(long, default_value = "utc")]
+        tz: String,
     },
 
     /// Échanger l'assignation d'un shift entre deux personnes
@@ -81,6 +474,51 @@ enum Commands {
         person: String,
         #[arg(long)]
         with: String,
+        #[arg(long, default_value_t = 11)]
+        min_rest_hours: u32,
+        #[arg(long, default_value_t = 3)]
+        max_consecutive_shifts: u32,
+        /// Interdire le même férié deux années de suite
+        #[arg(long)]
+        holiday_rotation: bool,
+        #[command(flatten)]
+        holidays: HolidayArgs,
+        /// Passer outre les règles enfreintes (verrou compris), tracées dans l'audit
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Annuler un cover : rendre un segment au titulaire du segment précédent
+    Uncover {
+        /// Segment créé par `cover`
+        #[arg(long)]
+        shift_id: String,
+        #[arg(long, default_value_t = 11)]
+        min_rest_hours: u32,
+        #[arg(long, default_value_t = 3)]
+        max_consecutive_shifts: u32,
+    },
+
+    /// Recoller les segments contigus d'un shift tenus par la même personne
+    MergeShifts {
+        /// Shift d'origine ou l'un de ses segments
+        #[arg(long)]
+        shift_id: String,
+    },
+
+    /// Verrouiller un shift (assignation négociée, préservée par `assign`)
+    Lock {
+        #[arg(long)]
+        shift_id: String,
+        /// Assigner le shift à cette personne avant de le verrouiller
+        #[arg(long)]
+        handle: Option<String>,
+    },
+
+    /// Déverrouiller un shift
+    Unlock {
+        #[arg(long)]
+        shift_id: String,
     },
 
     /// Couvrir la fin d'un shift à partir d'une date donnée
@@ -96,6 +534,58 @@ enum Commands {
         min_rest_hours: u32,
         #[arg(long, default_value_t = 3)]
         max_consecutive_shifts: u32,
+        /// Autoriser la couverture d'un shift verrouillé
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Retirer quelqu'un de la rotation et réassigner ses shifts futurs
+    Offboard {
+        #[arg(long)]
+        handle: String,
+        /// Date de départ (RFC3339 ou YYYY-MM-DD)
+        #[arg(long)]
+        from: String,
+        #[arg(long, default_value_t = 11)]
+        min_rest_hours: u32,
+        #[arg(long, default_value_t = 3)]
+        max_consecutive_shifts: u32,
+    },
+
+    /// Gérer les congés sans réimporter le CSV
+    Vacation {
+        #[command(subcommand)]
+        cmd: VacationCmd,
+    },
+
+    /// Gérer les équipes et leurs rotations
+    Team {
+        #[command(subcommand)]
+        cmd: TeamCmd,
+    },
+
+    /// Rotations « follow-the-sun » entre équipes régionales
+    FollowTheSun {
+        #[command(subcommand)]
+        cmd: FollowTheSunCmd,
+    },
+
+    /// Gérer les remplacements ponctuels
+    Override {
+        #[command(subcommand)]
+        cmd: OverrideCmd,
+    },
+
+    /// Négocier des échanges de shifts
+    SwapRequest {
+        #[command(subcommand)]
+        cmd: SwapRequestCmd,
+    },
+
+    /// Gérer les indisponibilités et préférences récurrentes
+    Availability {
+        #[command(subcommand)]
+        cmd: AvailabilityCmd,
     },
 
     /// Vérifier les conflits
@@ -107,6 +597,97 @@ enum Commands {
         /// Export CSV des conflits (optionnel)
         #[arg(long)]
         report: Option<String>,
+        /// Fuseau pour mois/week-ends des limites individuelles
+        /// (défaut : fuseau enregistré dans le roster)
+        #[arg(long)]
+        tz: Option<String>,
+        /// Conflits touchant un shift de cette équipe (détection inter-équipes)
+        #[arg(long)]
+        team: Option<String>,
+    },
+
+    /// Afficher ou modifier les limites individuelles d'une personne
+    Limits {
+        #[arg(long)]
+        handle: String,
+        #[arg(long)]
+        max_shifts_per_month: Option<u32>,
+        /// Heures max sur 7 jours glissants
+        #[arg(long)]
+        max_hours_per_7_days: Option<u32>,
+        #[arg(long)]
+        max_weekends_per_quarter: Option<u32>,
+        /// Repos minimal propre (remplace le global)
+        #[arg(long)]
+        min_rest_hours: Option<u32>,
+        #[arg(long)]
+        max_consecutive_shifts: Option<u32>,
+        /// Effacer toutes les limites avant d'appliquer les nouvelles
+        #[arg(long)]
+        clear: bool,
+    },
+
+    /// Afficher qui est d'astreinte à un instant donné (sortie JSON)
+    #[command(group(ArgGroup::new("when").required(true).args(["at", "now"])))]
+    Who {
+        /// Instant RFC3339 UTC
+        #[arg(long)]
+        at: Option<String>,
+        /// Utiliser l'instant présent
+        #[arg(long)]
+        now: bool,
+        /// Restreindre à un rôle
+        #[arg(long)]
+        role: Option<String>,
+        /// Restreindre à une équipe
+        #[arg(long)]
+        team: Option<String>,
+        /// Chaînes d'escalade résolues des équipes (export JSON pour l'alerting)
+        #[arg(long)]
+        escalation: bool,
+    },
+
+    /// Statistiques de charge par personne et par période
+    Stats {
+        /// Période: month, quarter, year, all
+        #[arg(long, default_value = "month")]
+        period: String,
+        /// Shifts se terminant après (RFC3339 ou YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Shifts commençant avant (RFC3339 ou YYYY-MM-DD inclus)
+        #[arg(long)]
+        until: Option<String>,
+        /// Début de nuit (HH:MM, heure locale)
+        #[arg(long, default_value = "22:00")]
+        night_start: String,
+        /// Fin de nuit (HH:MM, heure locale)
+        #[arg(long, default_value = "06:00")]
+        night_end: String,
+        #[command(flatten)]
+        holidays: HolidayArgs,
+        #[arg(long)]
+        out_json: Option<String>,
+        #[arg(long)]
+        out_csv: Option<String>,
+    },
+
+    /// Export de paie mensuel par personne (heures semaine/week-end/férié)
+    Payroll {
+        /// Grille de taux JSON (`weekday`, `weekend`, `holiday`, `intervention`)
+        #[arg(long)]
+        rates: String,
+        /// Mois à exporter (YYYY-MM), tous par défaut
+        #[arg(long)]
+        month: Option<String>,
+        #[command(flatten)]
+        holidays: HolidayArgs,
+        /// CSV d'interventions `handle,start,end`
+        #[arg(long)]
+        interventions: Option<String>,
+        /// Fichier CSV de sortie
+        #[arg(long)]
+        out: String,
     },
 
     /// Générer un rappel texte pour un membre d'astreinte
@@ -121,6 +702,89 @@ enum Commands {
     },
 }
 
+fn find_person_mut<'a>(scheduler: &'a mut Scheduler, handle: &str) -> Result<&'a mut Person> {
+    scheduler
+        .roster_mut()
+        .people
+        .iter_mut()
+        .find(|p| p.handle == handle)
+        .ok_or_else(|| anyhow::anyhow!("unknown person: {}", handle))
+}
+
+fn require_team(scheduler: &Scheduler, name: &str) -> Result<()> {
+    if scheduler.roster().find_team(name).is_none() {
+        bail!("unknown team: {name}");
+    }
+    Ok(())
+}
+
+fn report_assigned(scheduler: &Scheduler, touched: &[ShiftId]) {
+    let left = touched
+        .iter()
+        .filter_map(|id| scheduler.roster().find_shift(id))
+        .filter(|s| s.assigned.is_none())
+        .count();
+    println!(
+        "{} shift(s) processed, {} left unassigned",
+        touched.len(),
+        left
+    );
+}
+
+/// Affiche les défauts de couverture ; code 2 s'il y en a (comme `check`).
+fn print_coverage(issues: &[CoverageIssue]) -> i32 {
+    if issues.is_empty() {
+        println!("OK: continuous coverage");
+        return 0;
+    }
+    eprintln!("Found {} coverage issue(s)", issues.len());
+    for issue in issues {
+        println!(
+            "{} | {} | {}",
+            issue.kind.as_str(),
+            issue.start.to_rfc3339(),
+            issue.end.to_rfc3339()
+        );
+    }
+    2
+}
+
+/// Lignes ignorées par `--skip-invalid` ; code 2 s'il y en a.
+fn print_rejected(rejected: &[io::RowError]) -> i32 {
+    if rejected.is_empty() {
+        return 0;
+    }
+    eprintln!("Skipped {} invalid row(s)", rejected.len());
+    for e in rejected {
+        println!("! {e}");
+    }
+    2
+}
+
+/// `primary:5`, `secondary:10`, `@handle` (minutes à 0 par défaut).
+fn escalation_level(scheduler: &Scheduler, raw: &str) -> Result<EscalationLevel> {
+    let (target, minutes) = match raw.rsplit_once(':') {
+        Some((target, minutes)) => (target, minutes.trim().parse()?),
+        None => (raw, 0),
+    };
+    let target = match target.trim().strip_prefix('@') {
+        Some(handle) => EscalationTarget::Person(person_id(scheduler, handle)?),
+        None => EscalationTarget::OnCall(target.parse().map_err(anyhow::Error::msg)?),
+    };
+    Ok(EscalationLevel {
+        target,
+        escalate_after_minutes: minutes,
+    })
+}
+
+fn person_id(scheduler: &Scheduler, handle: &str) -> Result<PersonId> {
+    scheduler
+        .roster()
+        .find_person_by_handle(handle)
+        .map(|p| p.id.clone())
+        .ok_or_else(|| anyhow::anyhow!("unknown person: {}", handle))
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -142,87 +806,282 @@ fn main() -> Result<()> {
     };
 
     let code = match cli.cmd {
-        Commands::CreateShift { name, start, end } => {
+        Commands::CreateShift {
+            name,
+            start,
+            end,
+            role,
+            team,
+        } => {
             let start = start.parse()?;
             let end = end.parse()?;
-            scheduler.create_shift(&name, start, end)?;
+            if let Some(team) = &team {
+                require_team(&scheduler, team)?;
+            }
+            let id = scheduler.create_shift(&name, start, end)?;
+            let role: Option<Role> = role
+                .map(|r| r.parse())
+                .transpose()
+                .map_err(anyhow::Error::msg)?;
+            if let Some(shift) = scheduler.roster_mut().find_shift_mut(&id) {
+                shift.role = role;
+                shift.team = team;
+            }
             storage.save(scheduler.roster())?;
             0
         }
-        Commands::ImportPeople { csv } => {
-            let people = io::import_people_csv(csv)?;
-            scheduler.add_people(people);
+        Commands::ImportPeople {
+            csv,
+            dry_run,
+            prune,
+            skip_invalid,
+        } => {
+            let (people, rejected) = io::import_people_csv_with(csv, skip_invalid)?;
+            // une ligne écartée ne doit pas faire désactiver la personne
+            let kept: Vec<String> = rejected.iter().filter_map(|e| e.handle.clone()).collect();
+            let diff = scheduler
+                .roster_mut()
+                .upsert_people_with(people, prune, &kept);
+            for h in &diff.added {
+                println!("+ {h}");
+            }
+            for (h, fields) in &diff.updated {
+                println!("~ {h} ({})", fields.join(", "));
+            }
+            for h in &diff.deactivated {
+                println!("- {h} (deactivated)");
+            }
+            println!(
+                "{} added, {} updated, {} unchanged, {} deactivated",
+                diff.added.len(),
+                diff.updated.len(),
+                diff.unchanged.len(),
+                diff.deactivated.len()
+            );
+            if dry_run {
+                println!("dry run: nothing saved");
+            } else {
+                storage.save(scheduler.roster())?;
+            }
+            print_rejected(&rejected)
+        }
+        Commands::ImportSchedule { format, file } => {
+            let format: io::ScheduleFormat = format.parse().map_err(anyhow::Error::msg)?;
+            let imported = io::import_schedule_json(file, format)?;
+            let summary = io::merge_schedule(scheduler.roster_mut(), imported);
+            for handle in &summary.people_added {
+                println!("+ {handle}");
+            }
+            for id in &summary.reassigned {
+                println!("~ {} (assigned)", id.as_str());
+            }
+            println!(
+                "{} person(s) added, {} shift(s) added, {} matched, {} override(s) imported",
+                summary.people_added.len(),
+                summary.shifts,
+                summary.matched,
+                summary.overrides
+            );
             storage.save(scheduler.roster())?;
             0
         }
-        Commands::ImportShifts { csv } => {
-            let shifts = io::import_shifts_csv(csv)?;
-            scheduler.roster_mut().shifts.extend(shifts);
-            storage.save(scheduler.roster())?;
+        Commands::ExportSchedule { format, out, name } => {
+            let format: io::ScheduleFormat = format.parse().map_err(anyhow::Error::msg)?;
+            io::export_schedule_json(out, scheduler.roster(), format, &name)?;
+            0
+        }
+        #[cfg(feature = "spreadsheet")]
+        Commands::ExportPlanning {
+            out,
+            from,
+            days,
+            team,
+            holidays,
+        } => {
+            if let Some(team) = &team {
+                require_team(&scheduler, team)?;
+            }
+            let from = io::parse_instant(&from, false)?.date_naive();
+            let (calendar, zone) = holidays.resolve(scheduler.roster())?;
+            let grid = io::planning_grid(
+                scheduler.roster(),
+                from,
+                days,
+                zone,
+                &calendar,
+                team.as_deref(),
+            );
+            io::export_planning_xlsx(&out, &grid)?;
+            println!("{} day(s) written to {out}", grid.days.len());
             0
         }
+        Commands::ImportShifts {
+            csv,
+            dry_run,
+            team,
+            delimiter,
+            tz,
+            columns,
+            skip_invalid,
+        } => {
+            if let Some(team) = &team {
+                require_team(&scheduler, team)?;
+            }
+            if !delimiter.is_ascii() {
+                bail!("delimiter must be an ASCII character");
+            }
+            let mut opts = io::ShiftImportOptions {
+                delimiter: delimiter as u8,
+                zone: tz.parse().map_err(anyhow::Error::msg)?,
+                team,
+                skip_invalid,
+                ..io::ShiftImportOptions::default()
+            };
+            for raw in &columns {
+                let (column, header) = raw.split_once('=').ok_or_else(|| {
+                    anyhow::anyhow!("invalid --map (expected column=Header): {}", raw)
+                })?;
+                opts.columns
+                    .insert(column.trim().to_string(), header.trim().to_string());
+            }
+            let diff = io::import_shifts_into(csv, scheduler.roster_mut(), &opts)?;
+            for id in &diff.added {
+                println!("+ {}", id.as_str());
+            }
+            for (id, fields) in &diff.updated {
+                println!("~ {} ({})", id.as_str(), fields.join(", "));
+            }
+            println!(
+                "{} added, {} updated, {} unchanged",
+                diff.added.len(),
+                diff.updated.len(),
+                diff.unchanged.len()
+            );
+            if dry_run {
+                println!("dry run: nothing saved");
+            } else {
+                storage.save(scheduler.roster())?;
+            }
+            print_rejected(&diff.rejected)
+        }
         Commands::Assign {
             people,
             min_rest_hours,
             max_consecutive_shifts,
+            balance_holidays,
+            holiday_rotation,
+            holidays,
+            only_unassigned,
+            from,
+            until,
+            team,
         } => {
             let opts = AssignOptions {
                 min_rest_hours,
                 max_consecutive_shifts,
+                balance_holidays,
+                holiday_rotation,
             };
-            let mut persons: Vec<Person> = if let Some(list) = people {
-                let set: Vec<String> = list
-                    .split(',')
-                    .map(|s| s.trim().to_string())
-                    .filter(|s| !s.is_empty())
-                    .collect();
-                let mut out = Vec::new();
-                for h in set {
-                    if let Some(p) = scheduler.roster().people.iter().find(|p| p.handle == h) {
-                        out.push(p.clone());
+            let (calendar, zone) = holidays.resolve(scheduler.roster())?;
+            scheduler.set_holidays(calendar, zone);
+            let incremental = only_unassigned || from.is_some() || until.is_some();
+            let scope = AssignScope {
+                only_unassigned,
+                from: from.map(|f| io::parse_instant(&f, false)).transpose()?,
+                until: until.map(|u| io::parse_instant(&u, true)).transpose()?,
+                // les shifts passés ou en cours ne sont jamais touchés
+                now: incremental.then(Utc::now),
+            };
+            if let Some(team) = team {
+                let touched = scheduler.assign_team(&team, scope)?;
+                report_assigned(&scheduler, &touched);
+                storage.save(scheduler.roster())?;
+                0
+            } else {
+                let mut persons: Vec<Person> = if let Some(list) = people {
+                    let set: Vec<String> = list
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect();
+                    let mut out = Vec::new();
+                    for h in set {
+                        if let Some(p) = scheduler.roster().people.iter().find(|p| p.handle == h) {
+                            out.push(p.clone());
+                        }
                     }
+                    out
+                } else {
+                    scheduler.roster().people.clone()
+                };
+                persons.retain(|p| !p.on_vacation && p.is_active());
+                if persons.is_empty() {
+                    bail!("aucune personne disponible (vacances ou indisponibilités)");
                 }
-                out
-            } else {
-                scheduler.roster().people.clone()
-            };
-            persons.retain(|p| !p.on_vacation);
-            if persons.is_empty() {
-                bail!("aucune personne disponible (vacances ou indisponibilités)");
+                if incremental {
+                    let touched = scheduler.assign_incremental(&persons, opts, scope)?;
+                    report_assigned(&scheduler, &touched);
+                } else {
+                    scheduler.assign_rotative(&persons, opts)?;
+                }
+                storage.save(scheduler.roster())?;
+                0
             }
-            scheduler.assign_rotative(&persons, opts)?;
-            storage.save(scheduler.roster())?;
-            0
         }
-        Commands::List { out_json, out_csv } => {
+        Commands::List {
+            out_json,
+            out_csv,
+            with_overrides,
+            handle,
+            from,
+            until,
+            unassigned,
+            role,
+            name,
+            team,
+            sort,
+            format,
+            tz,
+        } => {
             if let Some(path) = out_json {
                 io::export_roster_json(path, scheduler.roster())?;
             }
             if let Some(path) = out_csv {
-                io::export_shifts_csv(path, scheduler.roster())?;
-            }
-            // impression compacte
-            for s in &scheduler.roster().shifts {
-                let assigned = s
-                    .assigned
-                    .as_ref()
-                    .and_then(|pid| scheduler.roster().people.iter().find(|p| p.id == *pid))
-                    .map(|p| p.handle.as_str())
-                    .unwrap_or("-");
-                println!(
-                    "{} | {} → {} | {}",
-                    s.id.as_str(),
-                    s.start.to_rfc3339(),
-                    s.end.to_rfc3339(),
-                    assigned
-                );
+                io::export_shifts_csv_with(path, scheduler.roster(), with_overrides)?;
             }
+            let query = ShiftQuery {
+                filter: ShiftFilter {
+                    handle,
+                    from: from.map(|f| io::parse_instant(&f, false)).transpose()?,
+                    until: until.map(|u| io::parse_instant(&u, true)).transpose()?,
+                    unassigned,
+                    role: role
+                        .map(|r| r.parse::<Role>())
+                        .transpose()
+                        .map_err(anyhow::Error::msg)?,
+                    name,
+                    team,
+                },
+                sort: sort
+                    .map(|k| k.parse::<SortKey>())
+                    .transpose()
+                    .map_err(anyhow::Error::msg)?,
+            };
+            let format: OutputFormat = format.parse().map_err(anyhow::Error::msg)?;
+            let zone: Zone = tz.parse().map_err(anyhow::Error::msg)?;
+            let rows = query.run(scheduler.roster());
+            print!("{}", query::render(&rows, format, zone)?);
             0
         }
         Commands::Swap {
             shift_id,
             person,
             with,
+            min_rest_hours,
+            max_consecutive_shifts,
+            holiday_rotation,
+            holidays,
+            force,
         } => {
             let sid = ShiftId::new(shift_id);
             let pa = scheduler
@@ -235,7 +1094,65 @@ fn main() -> Result<()> {
                 .find_person_by_handle(&with)
                 .map(|p| p.id.clone())
                 .ok_or_else(|| anyhow::anyhow!("unknown person: {}", with))?;
-            scheduler.swap(&sid, &pa, &pb, AssignOptions::default())?;
+            let opts = AssignOptions {
+                min_rest_hours,
+                max_consecutive_shifts,
+                holiday_rotation,
+                ..AssignOptions::default()
+            };
+            let (calendar, zone) = holidays.resolve(scheduler.roster())?;
+            scheduler.set_holidays(calendar, zone);
+            if force {
+                scheduler.swap_forced(&sid, &pa, &pb, opts)?;
+                let entry = scheduler.roster().audit.last();
+                if let Some(forced) = entry.map(|e| &e.forced).filter(|f| !f.is_empty()) {
+                    eprintln!("forced despite: {}", forced.join(", "));
+                }
+            } else {
+                scheduler.swap(&sid, &pa, &pb, opts)?;
+            }
+            storage.save(scheduler.roster())?;
+            0
+        }
+        Commands::Uncover {
+            shift_id,
+            min_rest_hours,
+            max_consecutive_shifts,
+        } => {
+            let opts = AssignOptions {
+                min_rest_hours,
+                max_consecutive_shifts,
+                ..AssignOptions::default()
+            };
+            let kept = scheduler.uncover(&ShiftId::new(shift_id), opts)?;
+            storage.save(scheduler.roster())?;
+            println!("{}", kept.as_str());
+            0
+        }
+        Commands::MergeShifts { shift_id } => {
+            let merged = scheduler.merge_shifts(&ShiftId::new(shift_id))?;
+            storage.save(scheduler.roster())?;
+            println!("{merged} segment(s) merged");
+            0
+        }
+        Commands::Lock { shift_id, handle } => {
+            let sid = ShiftId::new(shift_id);
+            let pid = match handle {
+                Some(h) => Some(
+                    scheduler
+                        .roster()
+                        .find_person_by_handle(&h)
+                        .map(|p| p.id.clone())
+                        .ok_or_else(|| anyhow::anyhow!("unknown person: {}", h))?,
+                ),
+                None => None,
+            };
+            scheduler.set_locked(&sid, true, pid.as_ref())?;
+            storage.save(scheduler.roster())?;
+            0
+        }
+        Commands::Unlock { shift_id } => {
+            scheduler.set_locked(&ShiftId::new(shift_id), false, None)?;
             storage.save(scheduler.roster())?;
             0
         }
@@ -245,6 +1162,7 @@ fn main() -> Result<()> {
             with,
             min_rest_hours,
             max_consecutive_shifts,
+            force,
         } => {
             let sid = ShiftId::new(shift_id);
             let at = from.parse()?;
@@ -256,8 +1174,496 @@ fn main() -> Result<()> {
             let opts = AssignOptions {
                 min_rest_hours,
                 max_consecutive_shifts,
+                ..AssignOptions::default()
             };
-            scheduler.cover_shift(&sid, at, &cover_id, opts)?;
+            if force {
+                scheduler.cover_shift_forced(&sid, at, &cover_id, opts)?;
+            } else {
+                scheduler.cover_shift(&sid, at, &cover_id, opts)?;
+            }
+            storage.save(scheduler.roster())?;
+            0
+        }
+        Commands::Offboard {
+            handle,
+            from,
+            min_rest_hours,
+            max_consecutive_shifts,
+        } => {
+            let from = io::parse_instant(&from, false)?;
+            let pid = scheduler
+                .roster()
+                .find_person_by_handle(&handle)
+                .map(|p| p.id.clone())
+                .ok_or_else(|| anyhow::anyhow!("unknown person: {}", handle))?;
+            let opts = AssignOptions {
+                min_rest_hours,
+                max_consecutive_shifts,
+                ..AssignOptions::default()
+            };
+            let pool: Vec<Person> = scheduler
+                .roster()
+                .people
+                .iter()
+                .filter(|p| p.is_active() && !p.on_vacation)
+                .cloned()
+                .collect();
+            let report = scheduler.offboard(&pid, from, &pool, opts)?;
+            for id in &report.unassigned {
+                let new = report
+                    .reassigned
+                    .iter()
+                    .find(|(sid, _)| sid == id)
+                    .and_then(|(_, pid)| scheduler.roster().find_person_by_id(pid))
+                    .map(|p| p.handle.as_str())
+                    .unwrap_or("-");
+                println!("{} | {} → {}", id.as_str(), handle, new);
+            }
+            for o in &report.removed_overrides {
+                println!(
+                    "{} | override {} - {} removed",
+                    o.shift_id.as_str(),
+                    o.start.to_rfc3339(),
+                    o.end.to_rfc3339()
+                );
+            }
+            storage.save(scheduler.roster())?;
+            if report.reassigned.len() < report.unassigned.len() {
+                eprintln!(
+                    "{} shift(s) left unassigned",
+                    report.unassigned.len() - report.reassigned.len()
+                );
+                2
+            } else {
+                0
+            }
+        }
+        Commands::Team { cmd } => match cmd {
+            TeamCmd::Add {
+                name,
+                members,
+                min_rest_hours,
+                max_consecutive_shifts,
+                balance_holidays,
+                holiday_rotation,
+            } => {
+                if scheduler.roster().find_team(&name).is_some() {
+                    bail!("team already exists: {name}");
+                }
+                let members = members
+                    .iter()
+                    .map(|h| person_id(&scheduler, h.trim()))
+                    .collect::<Result<Vec<_>>>()?;
+                scheduler.roster_mut().teams.push(Team {
+                    name,
+                    members,
+                    options: AssignOptions {
+                        min_rest_hours,
+                        max_consecutive_shifts,
+                        balance_holidays,
+                        holiday_rotation,
+                    },
+                    escalation: None,
+                });
+                storage.save(scheduler.roster())?;
+                0
+            }
+            TeamCmd::AddMember { name, handle } => {
+                let pid = person_id(&scheduler, &handle)?;
+                let team = scheduler
+                    .roster_mut()
+                    .find_team_mut(&name)
+                    .ok_or_else(|| anyhow::anyhow!("unknown team: {}", name))?;
+                if !team.members.contains(&pid) {
+                    team.members.push(pid);
+                }
+                storage.save(scheduler.roster())?;
+                0
+            }
+            TeamCmd::RemoveMember { name, handle } => {
+                let pid = person_id(&scheduler, &handle)?;
+                let team = scheduler
+                    .roster_mut()
+                    .find_team_mut(&name)
+                    .ok_or_else(|| anyhow::anyhow!("unknown team: {}", name))?;
+                team.members.retain(|m| m != &pid);
+                storage.save(scheduler.roster())?;
+                0
+            }
+            TeamCmd::Escalation {
+                name,
+                levels,
+                clear,
+            } => {
+                let policy = if clear {
+                    None
+                } else {
+                    let levels = levels
+                        .iter()
+                        .map(|raw| escalation_level(&scheduler, raw))
+                        .collect::<Result<Vec<_>>>()?;
+                    Some(EscalationPolicy { levels })
+                };
+                let team = scheduler
+                    .roster_mut()
+                    .find_team_mut(&name)
+                    .ok_or_else(|| anyhow::anyhow!("unknown team: {}", name))?;
+                team.escalation = policy;
+                storage.save(scheduler.roster())?;
+                0
+            }
+            TeamCmd::List => {
+                let roster = scheduler.roster();
+                for team in &roster.teams {
+                    let members: Vec<&str> = team
+                        .members
+                        .iter()
+                        .filter_map(|pid| roster.find_person_by_id(pid))
+                        .map(|p| p.handle.as_str())
+                        .collect();
+                    let shifts = roster
+                        .shifts
+                        .iter()
+                        .filter(|s| s.team.as_deref() == Some(team.name.as_str()))
+                        .count();
+                    println!(
+                        "{} | {} | {} shift(s) | rest {}h, max {} consecutive",
+                        team.name,
+                        members.join(","),
+                        shifts,
+                        team.options.min_rest_hours,
+                        team.options.max_consecutive_shifts
+                    );
+                }
+                0
+            }
+        },
+        Commands::FollowTheSun { cmd } => match cmd {
+            FollowTheSunCmd::Add { name, regions } => {
+                if scheduler.roster().find_follow_the_sun(&name).is_some() {
+                    bail!("follow-the-sun rotation already exists: {name}");
+                }
+                let regions = regions
+                    .iter()
+                    .map(|r| r.parse::<Region>().map_err(anyhow::Error::msg))
+                    .collect::<Result<Vec<_>>>()?;
+                for region in &regions {
+                    require_team(&scheduler, &region.team)?;
+                }
+                scheduler
+                    .roster_mut()
+                    .follow_the_sun
+                    .push(FollowTheSun { name, regions });
+                storage.save(scheduler.roster())?;
+                0
+            }
+            FollowTheSunCmd::Generate { name, from, days } => {
+                let from = io::parse_instant(&from, false)?.date_naive();
+                let created = scheduler.generate_follow_the_sun(&name, from, days)?;
+                let shifts: Vec<_> = created
+                    .iter()
+                    .filter_map(|id| scheduler.roster().find_shift(id))
+                    .collect();
+                let (Some(start), Some(end)) = (
+                    shifts.iter().map(|s| s.start).min(),
+                    shifts.iter().map(|s| s.end).max(),
+                ) else {
+                    bail!("no shift generated");
+                };
+                let scope = AssignScope {
+                    only_unassigned: true,
+                    from: Some(start),
+                    until: Some(end),
+                    now: None,
+                };
+                let touched = scheduler.assign_follow_the_sun(&name, scope)?;
+                report_assigned(&scheduler, &touched);
+                let issues = scheduler.follow_the_sun_coverage(&name, start, end)?;
+                storage.save(scheduler.roster())?;
+                print_coverage(&issues)
+            }
+            FollowTheSunCmd::Check { name, from, until } => {
+                let from = io::parse_instant(&from, false)?;
+                let until = io::parse_instant(&until, true)?;
+                let issues = scheduler.follow_the_sun_coverage(&name, from, until)?;
+                print_coverage(&issues)
+            }
+            FollowTheSunCmd::List => {
+                for rotation in &scheduler.roster().follow_the_sun {
+                    let regions: Vec<String> =
+                        rotation.regions.iter().map(ToString::to_string).collect();
+                    println!("{} | {}", rotation.name, regions.join(" -> "));
+                }
+                0
+            }
+        },
+        Commands::Override { cmd } => match cmd {
+            OverrideCmd::Add {
+                shift_id,
+                from,
+                until,
+                with,
+                min_rest_hours,
+                max_consecutive_shifts,
+            } => {
+                let pid = person_id(&scheduler, &with)?;
+                let opts = AssignOptions {
+                    min_rest_hours,
+                    max_consecutive_shifts,
+                    ..AssignOptions::default()
+                };
+                scheduler.add_override(
+                    &ShiftId::new(shift_id),
+                    from.parse()?,
+                    until.parse()?,
+                    &pid,
+                    opts,
+                )?;
+                storage.save(scheduler.roster())?;
+                0
+            }
+            OverrideCmd::Remove { shift_id, index } => {
+                scheduler.remove_override(&ShiftId::new(shift_id), index)?;
+                storage.save(scheduler.roster())?;
+                0
+            }
+            OverrideCmd::List { shift_id } => {
+                let roster = scheduler.roster();
+                for shift in &roster.shifts {
+                    if shift_id.as_ref().is_some_and(|id| id != shift.id.as_str()) {
+                        continue;
+                    }
+                    for (idx, o) in roster.overrides_for(&shift.id).enumerate() {
+                        println!(
+                            "{} | {} | {} → {} | {}",
+                            shift.id.as_str(),
+                            idx,
+                            o.start.to_rfc3339(),
+                            o.end.to_rfc3339(),
+                            roster
+                                .find_person_by_id(&o.person)
+                                .map_or("?", |p| p.handle.as_str())
+                        );
+                    }
+                }
+                0
+            }
+        },
+        Commands::SwapRequest { cmd } => match cmd {
+            SwapRequestCmd::Propose {
+                from,
+                to,
+                give,
+                take,
+                expires,
+            } => {
+                let from = person_id(&scheduler, &from)?;
+                let to = person_id(&scheduler, &to)?;
+                let expires = expires.map(|e| io::parse_instant(&e, true)).transpose()?;
+                let id = scheduler.propose_swap(
+                    &from,
+                    &to,
+                    give.iter().map(ShiftId::new).collect(),
+                    take.iter().map(ShiftId::new).collect(),
+                    expires,
+                )?;
+                storage.save(scheduler.roster())?;
+                println!("{}", id.as_str());
+                0
+            }
+            SwapRequestCmd::Accept {
+                id,
+                min_rest_hours,
+                max_consecutive_shifts,
+            } => {
+                let opts = AssignOptions {
+                    min_rest_hours,
+                    max_consecutive_shifts,
+                    ..AssignOptions::default()
+                };
+                let id = SwapRequestId::new(id);
+                // une proposition échue est close même si l'acceptation échoue
+                scheduler.expire_swap_requests(Utc::now());
+                let res = scheduler.accept_swap_request(&id, opts);
+                storage.save(scheduler.roster())?;
+                res?;
+                0
+            }
+            SwapRequestCmd::Reject { id } => {
+                scheduler.reject_swap_request(&SwapRequestId::new(id))?;
+                storage.save(scheduler.roster())?;
+                0
+            }
+            SwapRequestCmd::List { handle, all } => {
+                if !scheduler.expire_swap_requests(Utc::now()).is_empty() {
+                    storage.save(scheduler.roster())?;
+                }
+                let roster = scheduler.roster();
+                let handle_of = |pid: &PersonId| {
+                    roster
+                        .find_person_by_id(pid)
+                        .map_or("?", |p| p.handle.as_str())
+                };
+                let ids = |ids: &[ShiftId]| {
+                    ids.iter()
+                        .map(ShiftId::as_str)
+                        .collect::<Vec<_>>()
+                        .join(",")
+                };
+                for r in &roster.swap_requests {
+                    if !all && r.status != SwapStatus::Pending {
+                        continue;
+                    }
+                    let (from, to) = (handle_of(&r.from), handle_of(&r.to));
+                    if handle.as_ref().is_some_and(|h| h != from && h != to) {
+                        continue;
+                    }
+                    println!(
+                        "{} | {} | {} → {} | give {} | take {} | expires {}",
+                        r.id.as_str(),
+                        r.status,
+                        from,
+                        to,
+                        ids(&r.offered),
+                        if r.requested.is_empty() {
+                            "-".to_string()
+                        } else {
+                            ids(&r.requested)
+                        },
+                        r.expires_at.map_or("-".to_string(), |e| e.to_rfc3339())
+                    );
+                }
+                0
+            }
+        },
+        Commands::Vacation { cmd } => match cmd {
+            VacationCmd::Add {
+                handle,
+                from,
+                until,
+                check,
+                min_rest_hours,
+            } => {
+                let period = io::parse_vacation_range(&from, until.as_deref())?;
+                let person = find_person_mut(&mut scheduler, &handle)?;
+                person.vacations.push(period);
+                person.vacations.sort_by_key(|v| v.start);
+                let pid = person.id.clone();
+                storage.save(scheduler.roster())?;
+                if check {
+                    let opts = AssignOptions {
+                        min_rest_hours,
+                        ..AssignOptions::default()
+                    };
+                    let impacted = scheduler.vacation_conflicts(&pid, opts);
+                    for id in &impacted {
+                        if let Some(s) = scheduler.roster().find_shift(id) {
+                            println!(
+                                "conflict | {} | {} → {} | {}",
+                                s.id.as_str(),
+                                s.start.to_rfc3339(),
+                                s.end.to_rfc3339(),
+                                handle
+                            );
+                        }
+                    }
+                    if impacted.is_empty() {
+                        println!("OK: no assigned shift impacted");
+                        0
+                    } else {
+                        eprintln!("{} assigned shift(s) now in conflict", impacted.len());
+                        2
+                    }
+                } else {
+                    0
+                }
+            }
+            VacationCmd::Remove { handle, index } => {
+                let person = find_person_mut(&mut scheduler, &handle)?;
+                if index >= person.vacations.len() {
+                    bail!("no vacation #{index} for {handle}");
+                }
+                let removed = person.vacations.remove(index);
+                println!(
+                    "removed {} → {} from {}",
+                    removed.start.to_rfc3339(),
+                    removed.end.to_rfc3339(),
+                    handle
+                );
+                storage.save(scheduler.roster())?;
+                0
+            }
+            VacationCmd::List { handle } => {
+                for p in &scheduler.roster().people {
+                    if handle.as_ref().is_some_and(|h| *h != p.handle) {
+                        continue;
+                    }
+                    for (idx, v) in p.vacations.iter().enumerate() {
+                        println!(
+                            "{} | {} | {} → {}",
+                            p.handle,
+                            idx,
+                            v.start.to_rfc3339(),
+                            v.end.to_rfc3339()
+                        );
+                    }
+                }
+                0
+            }
+        },
+        Commands::Availability { cmd } => match cmd {
+            AvailabilityCmd::Add { handle, rule } => {
+                let rule: AvailabilityRule = rule.parse().map_err(anyhow::Error::msg)?;
+                let person = find_person_mut(&mut scheduler, &handle)?;
+                person.availability.push(rule);
+                storage.save(scheduler.roster())?;
+                0
+            }
+            AvailabilityCmd::Remove { handle, index } => {
+                let person = find_person_mut(&mut scheduler, &handle)?;
+                if index >= person.availability.len() {
+                    bail!("no availability rule #{index} for {handle}");
+                }
+                let removed = person.availability.remove(index);
+                println!("removed {removed} from {handle}");
+                storage.save(scheduler.roster())?;
+                0
+            }
+            AvailabilityCmd::List { handle } => {
+                for p in &scheduler.roster().people {
+                    if handle.as_ref().is_some_and(|h| *h != p.handle) {
+                        continue;
+                    }
+                    for (idx, rule) in p.availability.iter().enumerate() {
+                        let strength = if rule.kind.is_hard() { "hard" } else { "soft" };
+                        println!("{} | {} | {} | {}", p.handle, idx, strength, rule);
+                    }
+                }
+                0
+            }
+        },
+        Commands::Limits {
+            handle,
+            max_shifts_per_month,
+            max_hours_per_7_days,
+            max_weekends_per_quarter,
+            min_rest_hours,
+            max_consecutive_shifts,
+            clear,
+        } => {
+            let person = find_person_mut(&mut scheduler, &handle)?;
+            let limits = &mut person.limits;
+            if clear {
+                *limits = Default::default();
+            }
+            limits.max_shifts_per_month = max_shifts_per_month.or(limits.max_shifts_per_month);
+            limits.max_hours_per_7_days = max_hours_per_7_days.or(limits.max_hours_per_7_days);
+            limits.max_weekends_per_quarter =
+                max_weekends_per_quarter.or(limits.max_weekends_per_quarter);
+            limits.min_rest_hours = min_rest_hours.or(limits.min_rest_hours);
+            limits.max_consecutive_shifts =
+                max_consecutive_shifts.or(limits.max_consecutive_shifts);
+            println!("{}: {}", handle, serde_json::to_string(limits)?);
             storage.save(scheduler.roster())?;
             0
         }
@@ -265,12 +1671,28 @@ fn main() -> Result<()> {
             min_rest_hours,
             max_consecutive_shifts,
             report,
+            tz,
+            team,
         } => {
+            if let Some(tz) = tz {
+                scheduler.set_zone(tz.parse::<Zone>().map_err(anyhow::Error::msg)?);
+            }
             let opts = AssignOptions {
                 min_rest_hours,
                 max_consecutive_shifts,
+                ..AssignOptions::default()
             };
-            let conflicts = scheduler.detect_conflicts(opts);
+            let mut conflicts = scheduler.detect_conflicts(opts);
+            if let Some(team) = &team {
+                require_team(&scheduler, team)?;
+                let roster = scheduler.roster();
+                let in_team = |id: &ShiftId| {
+                    roster
+                        .find_shift(id)
+                        .is_some_and(|s| s.team.as_deref() == Some(team.as_str()))
+                };
+                conflicts.retain(|c| in_team(&c.shift_a) || in_team(&c.shift_b));
+            }
             if conflicts.is_empty() {
                 println!("OK: no conflicts");
                 0
@@ -285,11 +1707,7 @@ fn main() -> Result<()> {
                             c.person.as_str(),
                             c.shift_a.as_str(),
                             c.shift_b.as_str(),
-                            match c.kind {
-                                ConflictKind::Overlap => "overlap",
-                                ConflictKind::DoubleAssignment => "double",
-                                ConflictKind::RestViolation => "rest",
-                            },
+                            c.kind.as_str(),
                         ])?;
                     }
                     w.flush()?;
@@ -298,6 +1716,174 @@ fn main() -> Result<()> {
                 2
             }
         }
+        Commands::Who {
+            at,
+            now: _,
+            role,
+            team,
+            escalation: true,
+        } => {
+            let at: DateTime<Utc> = match at {
+                Some(raw) => raw.parse()?,
+                None => Utc::now(),
+            };
+            if role.is_some() {
+                bail!("--role cannot be combined with --escalation");
+            }
+            if let Some(team) = &team {
+                require_team(&scheduler, team)?;
+            }
+            let roster = scheduler.roster();
+            let chains: Vec<(&str, Vec<EscalationStep>)> = roster
+                .teams
+                .iter()
+                .filter(|t| t.escalation.is_some())
+                .filter(|t| team.as_ref().map_or(true, |name| &t.name == name))
+                .map(|t| (t.name.as_str(), escalation::resolve(roster, t, at)))
+                .collect();
+            let entries: Vec<serde_json::Value> = chains
+                .iter()
+                .map(|(name, chain)| serde_json::json!({ "team": name, "chain": chain }))
+                .collect();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "at": at.to_rfc3339(),
+                    "escalation": entries,
+                }))?
+            );
+            // Code 2 = niveau sans personne à appeler
+            let unresolved = chains
+                .iter()
+                .flat_map(|(_, chain)| chain)
+                .any(|step| step.person_id.is_none());
+            if chains.is_empty() || unresolved {
+                2
+            } else {
+                0
+            }
+        }
+        Commands::Who {
+            at,
+            now: _,
+            role,
+            team,
+            escalation: false,
+        } => {
+            let at: DateTime<Utc> = match at {
+                Some(raw) => raw.parse()?,
+                None => Utc::now(),
+            };
+            let role: Option<Role> = role
+                .map(|r| r.parse())
+                .transpose()
+                .map_err(anyhow::Error::msg)?;
+            let roster = scheduler.roster();
+            let entries: Vec<serde_json::Value> = roster
+                .shifts_at(at, role.as_ref())
+                .into_iter()
+                .filter(|s| team.is_none() || s.team == team)
+                .filter_map(|s| {
+                    // portion en cours, remplacements ponctuels compris
+                    let seg = roster
+                        .segments(s)
+                        .into_iter()
+                        .find(|seg| seg.start <= at && at < seg.end)?;
+                    let p = roster.find_person_by_id(seg.holder?)?;
+                    Some(serde_json::json!({
+                        "handle": p.handle,
+                        "display_name": p.display_name,
+                        "person_id": p.id.as_str(),
+                        "shift_id": s.id.as_str(),
+                        "shift_name": s.name,
+                        "role": s.role.as_ref().map(|r| r.to_string()),
+                        "start": seg.start.to_rfc3339(),
+                        "end": seg.end.to_rfc3339(),
+                    }))
+                })
+                .collect();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "at": at.to_rfc3339(),
+                    "on_call": entries,
+                }))?
+            );
+            // Code 2 = personne d'astreinte (trou de couverture)
+            if entries.is_empty() {
+                2
+            } else {
+                0
+            }
+        }
+        Commands::Stats {
+            period,
+            from,
+            until,
+            night_start,
+            night_end,
+            holidays,
+            out_json,
+            out_csv,
+        } => {
+            let (holidays, zone) = holidays.resolve(scheduler.roster())?;
+            let opts = StatsOptions {
+                period: period.parse::<Period>().map_err(anyhow::Error::msg)?,
+                zone,
+                night_start: NaiveTime::parse_from_str(&night_start, "%H:%M")?,
+                night_end: NaiveTime::parse_from_str(&night_end, "%H:%M")?,
+                holidays,
+                from: from.map(|f| io::parse_instant(&f, false)).transpose()?,
+                until: until.map(|u| io::parse_instant(&u, true)).transpose()?,
+            };
+            let rows = stats::compute(scheduler.roster(), &opts);
+            if let Some(path) = out_json {
+                io::export_stats_json(path, &rows)?;
+            }
+            if let Some(path) = out_csv {
+                io::export_stats_csv(path, &rows)?;
+            }
+            for r in &rows {
+                println!(
+                    "{} | {} | {} shifts | {:.2} h (écart {:+.2}) | nuit {:.2} h | we {} | férié {} | covers {}/{} | swaps {}",
+                    r.period,
+                    r.handle,
+                    r.shifts,
+                    r.hours,
+                    r.hours_deviation,
+                    r.night_hours,
+                    r.weekend_shifts,
+                    r.holiday_shifts,
+                    r.covers_given,
+                    r.covers_received,
+                    r.swaps
+                );
+            }
+            0
+        }
+        Commands::Payroll {
+            rates,
+            month,
+            holidays,
+            interventions,
+            out,
+        } => {
+            let rates = RateTable::load(rates)?;
+            let (holidays, zone) = holidays.resolve(scheduler.roster())?;
+            let opts = PayrollOptions {
+                zone,
+                holidays,
+                month,
+            };
+            let interventions = match interventions {
+                Some(path) => io::import_interventions_csv(path)?,
+                None => Vec::new(),
+            };
+            let lines = payroll::compute(scheduler.roster(), &rates, &opts, &interventions);
+            io::export_payroll_csv(&out, &lines)?;
+            println!("{} payroll line(s) written to {}", lines.len(), out);
+            0
+        }
         Commands::Notify {
             handle,
             days_before,
@@ -0,0 +1,66 @@
+use crate::model::{EscalationTarget, PersonId, Role, Roster, ShiftId, Team};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Niveau résolu à un instant donné (format d'export JSON pour l'alerting).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EscalationStep {
+    /// Rang dans la chaîne, à partir de 1.
+    pub level: usize,
+    /// Minutes après le déclenchement de l'alerte où ce niveau est appelé.
+    pub notify_after_minutes: u32,
+    pub escalate_after_minutes: u32,
+    pub target: EscalationTarget,
+    /// Personne à appeler ; absente si personne n'est d'astreinte sur ce rôle.
+    pub person_id: Option<PersonId>,
+    pub handle: Option<String>,
+    pub display_name: Option<String>,
+    /// Shift couvrant ce niveau, pour un niveau d'astreinte.
+    pub shift_id: Option<ShiftId>,
+}
+
+/// Résout la chaîne d'escalade de `team` à l'instant `at` (vide sans politique).
+pub fn resolve(roster: &Roster, team: &Team, at: DateTime<Utc>) -> Vec<EscalationStep> {
+    let Some(policy) = &team.escalation else {
+        return Vec::new();
+    };
+    let mut notify_after = 0;
+    let mut out = Vec::new();
+    for (idx, level) in policy.levels.iter().enumerate() {
+        let (person, shift) = match &level.target {
+            EscalationTarget::OnCall(role) => {
+                let mut shifts: Vec<_> = roster
+                    .shifts
+                    .iter()
+                    .filter(|s| s.team.as_deref() == Some(team.name.as_str()))
+                    .filter(|s| match &s.role {
+                        Some(r) => r == role,
+                        None => *role == Role::Primary,
+                    })
+                    .filter_map(|s| roster.holder_at(s, at).map(|pid| (pid, s)))
+                    .collect();
+                shifts.sort_by_key(|(_, s)| s.start);
+                match shifts.first() {
+                    Some((pid, s)) => (Some((*pid).clone()), Some(s.id.clone())),
+                    None => (None, None),
+                }
+            }
+            EscalationTarget::Person(pid) => (Some(pid.clone()), None),
+        };
+        let found = person
+            .as_ref()
+            .and_then(|pid| roster.find_person_by_id(pid));
+        out.push(EscalationStep {
+            level: idx + 1,
+            notify_after_minutes: notify_after,
+            escalate_after_minutes: level.escalate_after_minutes,
+            target: level.target.clone(),
+            person_id: person,
+            handle: found.map(|p| p.handle.clone()),
+            display_name: found.map(|p| p.display_name.clone()),
+            shift_id: shift,
+        });
+        notify_after += level.escalate_after_minutes;
+    }
+    out
+}
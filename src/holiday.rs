@@ -0,0 +1,131 @@
+use anyhow::Context;
+use chrono::{Datelike, Duration, NaiveDate};
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// Dimanche de Pâques (calendrier grégorien, algorithme de Meeus/Jones/Butcher).
+pub fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).expect("valid Easter date")
+}
+
+/// Calendrier de jours fériés (dates locales).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HolidayCalendar {
+    dates: BTreeMap<NaiveDate, String>,
+}
+
+impl HolidayCalendar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Jours fériés légaux de France métropolitaine pour une année.
+    pub fn french(year: i32) -> Self {
+        let mut cal = Self::new();
+        let fixed = [
+            (1, 1, "Jour de l'an"),
+            (5, 1, "Fête du Travail"),
+            (5, 8, "Victoire 1945"),
+            (7, 14, "Fête nationale"),
+            (8, 15, "Assomption"),
+            (11, 1, "Toussaint"),
+            (11, 11, "Armistice"),
+            (12, 25, "Noël"),
+        ];
+        for (month, day, name) in fixed {
+            if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
+                cal.insert(date, name);
+            }
+        }
+        let easter = easter_sunday(year);
+        cal.insert(easter + Duration::days(1), "Lundi de Pâques");
+        cal.insert(easter + Duration::days(39), "Ascension");
+        cal.insert(easter + Duration::days(50), "Lundi de Pentecôte");
+        cal
+    }
+
+    /// Ajoute les jours fériés français des années données.
+    pub fn add_french_years(&mut self, years: RangeInclusive<i32>) {
+        for year in years {
+            self.merge(Self::french(year));
+        }
+    }
+
+    /// Fusionne un autre calendrier (ses noms l'emportent, sauf s'ils sont vides).
+    pub fn merge(&mut self, other: HolidayCalendar) {
+        for (date, name) in other.dates {
+            if name.is_empty() && self.dates.contains_key(&date) {
+                continue;
+            }
+            self.dates.insert(date, name);
+        }
+    }
+
+    pub fn insert<S: Into<String>>(&mut self, date: NaiveDate, name: S) {
+        self.dates.insert(date, name.into());
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.dates.contains_key(&date)
+    }
+
+    /// Nom du jour férié, s'il y en a un.
+    pub fn name(&self, date: NaiveDate) -> Option<&str> {
+        self.dates.get(&date).map(String::as_str)
+    }
+
+    /// Clé identifiant « le même » férié d'une année sur l'autre :
+    /// son nom, ou `MM-DD` pour une date personnalisée sans nom.
+    pub fn key(&self, date: NaiveDate) -> Option<String> {
+        let name = self.name(date)?;
+        if name.is_empty() {
+            Some(format!("{:02}-{:02}", date.month(), date.day()))
+        } else {
+            Some(name.to_string())
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dates.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (NaiveDate, &str)> {
+        self.dates.iter().map(|(d, n)| (*d, n.as_str()))
+    }
+
+    /// Charge des dates depuis un fichier texte : une ligne `YYYY-MM-DD[,nom]`,
+    /// lignes vides et commentaires `#` ignorés.
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let raw =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let mut cal = Self::new();
+        for (idx, line) in raw.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (date, name) = line.split_once(',').unwrap_or((line, ""));
+            let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+                .with_context(|| format!("invalid holiday date on line {}", idx + 1))?;
+            cal.insert(date, name.trim());
+        }
+        Ok(cal)
+    }
+}
@@ -1,39 +1,244 @@
-use crate::model::{Person, Roster, Shift, VacationPeriod};
+use crate::availability::AvailabilityRule;
+use crate::holiday::HolidayCalendar;
+use crate::model::{Override, Person, PersonId, Role, Roster, Shift, ShiftId, VacationPeriod};
+use crate::payroll::{Intervention, PayrollLine};
+use crate::stats::PersonStats;
+use crate::tz::Zone;
 use anyhow::{bail, Context};
-use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
 use csv::{ReaderBuilder, WriterBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
 
-/// Import de personnes depuis CSV: header `handle,display_name[,on_vacation][,vacations]`
+#[cfg(feature = "spreadsheet")]
+mod spreadsheet;
+#[cfg(feature = "spreadsheet")]
+pub use spreadsheet::export_planning_xlsx;
+#[cfg(feature = "spreadsheet")]
+use spreadsheet::read_sheet;
+
+/// Erreur de validation d'une ligne de CSV.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// Numéro de ligne dans le fichier (en-tête = 1).
+    pub line: u64,
+    /// Colonne fautive, quand l'erreur en concerne une.
+    pub column: Option<String>,
+    pub reason: String,
+    /// Handle de la personne décrite par la ligne, quand il a pu être lu.
+    pub handle: Option<String>,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}", self.line)?;
+        if let Some(column) = &self.column {
+            write!(f, ", column {column}")?;
+        }
+        write!(f, ": {}", self.reason)
+    }
+}
+
+/// Erreurs de validation d'un import, toutes lignes confondues ;
+/// rien n'est appliqué tant qu'il en reste.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportErrors(pub Vec<RowError>);
+
+impl fmt::Display for ImportErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} invalid row(s)", self.0.len())?;
+        for e in &self.0 {
+            write!(f, "\n  {e}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ImportErrors {}
+
+/// Erreur d'un champ, avant d'être rattachée à sa ligne.
+struct FieldError {
+    column: Option<&'static str>,
+    reason: String,
+}
+
+impl FieldError {
+    fn new(column: &'static str, reason: impl fmt::Display) -> Self {
+        Self {
+            column: Some(column),
+            reason: format!("{reason:#}"),
+        }
+    }
+
+    fn row(reason: impl fmt::Display) -> Self {
+        Self {
+            column: None,
+            reason: format!("{reason:#}"),
+        }
+    }
+
+    fn at(self, line: u64) -> RowError {
+        RowError {
+            line,
+            column: self.column.map(str::to_string),
+            reason: self.reason,
+            handle: None,
+        }
+    }
+}
+
+/// Valeur d'un champ, l'erreur éventuelle étant consignée.
+fn check_field<T, E: fmt::Display>(
+    errors: &mut Vec<FieldError>,
+    column: &'static str,
+    value: Result<T, E>,
+) -> Option<T> {
+    value
+        .map_err(|e| errors.push(FieldError::new(column, e)))
+        .ok()
+}
+
+/// Import de personnes depuis CSV: header `handle,display_name[,on_vacation][,vacations][,availability]`
+///
+/// Avec la feature `spreadsheet`, accepte aussi un classeur `.xlsx`/`.ods`
+/// (première feuille, mêmes colonnes).
+///
+/// Toutes les lignes sont validées ; la moindre erreur fait échouer l'import
+/// ([`ImportErrors`]).
 pub fn import_people_csv<P: AsRef<Path>>(path: P) -> anyhow::Result<Vec<Person>> {
-    let mut rdr = ReaderBuilder::new().has_headers(true).from_path(path)?;
-    let mut out = Vec::new();
+    import_people_csv_with(path, false).map(|(people, _)| people)
+}
+
+/// Import de personnes validé ligne à ligne : handles en double, valeurs
+/// invalides et périodes de congés qui se chevauchent sont signalés avec leur
+/// ligne et leur colonne. Avec `skip_invalid`, les lignes valides sont
+/// renvoyées avec les erreurs des autres ; sinon toute erreur est fatale.
+pub fn import_people_csv_with<P: AsRef<Path>>(
+    path: P,
+    skip_invalid: bool,
+) -> anyhow::Result<(Vec<Person>, Vec<RowError>)> {
+    let (_, rows) = read_rows(path.as_ref(), b',')?;
+    people_from_rows(rows, skip_invalid)
+}
+
+/// En-tête et lignes d'une feuille, numérotées comme dans le fichier (en-tête = 1).
+type Rows = (csv::StringRecord, Vec<(u64, csv::StringRecord)>);
+
+/// Extensions lues comme des classeurs plutôt que comme des CSV.
+pub const SPREADSHEET_EXTENSIONS: [&str; 5] = ["xlsx", "xlsm", "xlsb", "xls", "ods"];
+
+pub fn is_spreadsheet(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            SPREADSHEET_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+/// Lignes d'un CSV, ou de la première feuille d'un classeur.
+fn read_rows(path: &Path, delimiter: u8) -> anyhow::Result<Rows> {
+    if is_spreadsheet(path) {
+        read_sheet(path)
+    } else {
+        read_csv(path, delimiter)
+    }
+}
+
+#[cfg(not(feature = "spreadsheet"))]
+fn read_sheet(path: &Path) -> anyhow::Result<Rows> {
+    bail!(
+        "{}: spreadsheet import requires the `spreadsheet` feature",
+        path.display()
+    )
+}
+
+fn read_csv(path: &Path, delimiter: u8) -> anyhow::Result<Rows> {
+    let mut rdr = ReaderBuilder::new()
+        .has_headers(true)
+        .delimiter(delimiter)
+        .from_path(path)?;
+    let headers = rdr.headers()?.clone();
+    let mut rows = Vec::new();
     for rec in rdr.records() {
         let rec = rec?;
-        let handle = rec.get(0).context("missing handle")?.trim();
-        let display = rec.get(1).context("missing display_name")?.trim();
-        if handle.is_empty() || display.is_empty() {
-            bail!("invalid people row (empty)");
-        }
-        let mut person = Person::new(handle.to_string(), display.to_string());
-        if let Some(flag) = rec.get(2) {
-            let flag = flag.trim();
-            if !flag.is_empty() {
-                person.on_vacation = parse_bool(flag)
-                    .with_context(|| format!("invalid on_vacation value for handle {handle}"))?;
-            }
+        rows.push((rec.position().map_or(0, |p| p.line()), rec));
+    }
+    Ok((headers, rows))
+}
+
+fn people_from_rows(
+    rows: Vec<(u64, csv::StringRecord)>,
+    skip_invalid: bool,
+) -> anyhow::Result<(Vec<Person>, Vec<RowError>)> {
+    let mut out: Vec<(u64, Person)> = Vec::new();
+    let mut rejected = Vec::new();
+    for (line, rec) in rows {
+        let errors = match person_from_record(&rec) {
+            Ok(person) => match out.iter().find(|(_, p)| p.handle == person.handle) {
+                Some((first, _)) => vec![FieldError::new(
+                    "handle",
+                    format!("duplicate handle {} (line {first})", person.handle),
+                )],
+                None => {
+                    out.push((line, person));
+                    Vec::new()
+                }
+            },
+            Err(errors) => errors,
+        };
+        let handle = rec.get(0).map(str::trim).filter(|h| !h.is_empty());
+        rejected.extend(errors.into_iter().map(|e| RowError {
+            handle: handle.map(str::to_string),
+            ..e.at(line)
+        }));
+    }
+    if !rejected.is_empty() && !skip_invalid {
+        return Err(ImportErrors(rejected).into());
+    }
+    Ok((out.into_iter().map(|(_, p)| p).collect(), rejected))
+}
+
+fn person_from_record(rec: &csv::StringRecord) -> Result<Person, Vec<FieldError>> {
+    let mut errors = Vec::new();
+    let value = |idx| rec.get(idx).map(str::trim).unwrap_or("");
+    let (handle, display) = (value(0), value(1));
+    if handle.is_empty() {
+        errors.push(FieldError::new("handle", "missing value"));
+    }
+    if display.is_empty() {
+        errors.push(FieldError::new("display_name", "missing value"));
+    }
+    let mut person = Person::new(handle.to_string(), display.to_string());
+    if !value(2).is_empty() {
+        if let Some(flag) = check_field(&mut errors, "on_vacation", parse_bool(value(2))) {
+            person.on_vacation = flag;
         }
-        if let Some(ranges) = rec.get(3) {
-            let ranges = ranges.trim();
-            if !ranges.is_empty() {
-                person.vacations = parse_vacations(ranges)
-                    .with_context(|| format!("invalid vacations value for handle {handle}"))?;
+    }
+    if !value(3).is_empty() {
+        if let Some(mut vacations) =
+            check_field(&mut errors, "vacations", parse_vacations(value(3)))
+        {
+            vacations.sort_by_key(|v| v.start);
+            if vacations.windows(2).any(|w| w[1].start < w[0].end) {
+                errors.push(FieldError::new("vacations", "overlapping vacation ranges"));
             }
+            person.vacations = vacations;
         }
-        out.push(person);
     }
-    Ok(out)
+    if let Some(rules) = check_field(&mut errors, "availability", parse_availability(value(4))) {
+        person.availability = rules;
+    }
+    if errors.is_empty() {
+        Ok(person)
+    } else {
+        Err(errors)
+    }
 }
 
 fn parse_bool(s: &str) -> anyhow::Result<bool> {
@@ -44,6 +249,15 @@ fn parse_bool(s: &str) -> anyhow::Result<bool> {
     }
 }
 
+/// Règles de disponibilité séparées par `;` (voir `AvailabilityRule`).
+pub fn parse_availability(raw: &str) -> anyhow::Result<Vec<AvailabilityRule>> {
+    raw.split(';')
+        .map(str::trim)
+        .filter(|chunk| !chunk.is_empty())
+        .map(|chunk| chunk.parse().map_err(anyhow::Error::msg))
+        .collect()
+}
+
 fn parse_vacations(raw: &str) -> anyhow::Result<Vec<VacationPeriod>> {
     raw.split(';')
         .filter(|chunk| !chunk.trim().is_empty())
@@ -51,6 +265,15 @@ fn parse_vacations(raw: &str) -> anyhow::Result<Vec<VacationPeriod>> {
         .collect()
 }
 
+/// Période de congés `from`[..`until`] avec la sémantique de la colonne `vacations` :
+/// une date seule bloque la journée complète, une date de fin est incluse.
+pub fn parse_vacation_range(from: &str, until: Option<&str>) -> anyhow::Result<VacationPeriod> {
+    match until {
+        Some(until) => parse_vacation_chunk(&format!("{}/{}", from.trim(), until.trim())),
+        None => parse_vacation_chunk(from.trim()),
+    }
+}
+
 fn parse_vacation_chunk(chunk: &str) -> anyhow::Result<VacationPeriod> {
     if let Some((start_raw, end_raw)) = chunk.split_once('/').or_else(|| chunk.split_once("..")) {
         let (start, _) = parse_point(start_raw.trim())?;
@@ -66,6 +289,16 @@ fn parse_vacation_chunk(chunk: &str) -> anyhow::Result<VacationPeriod> {
     }
 }
 
+/// Instant RFC3339 ou date `YYYY-MM-DD` (minuit UTC).
+/// Avec `end_of_day`, une date seule désigne la fin de la journée (minuit du lendemain).
+pub fn parse_instant(raw: &str, end_of_day: bool) -> anyhow::Result<DateTime<Utc>> {
+    let (dt, was_date) = parse_point(raw.trim())?;
+    if was_date && end_of_day {
+        return Ok(dt + Duration::days(1));
+    }
+    Ok(dt)
+}
+
 fn parse_point(raw: &str) -> anyhow::Result<(DateTime<Utc>, bool)> {
     if let Ok(dt) = raw.parse::<DateTime<Utc>>() {
         return Ok((dt, false));
@@ -78,19 +311,242 @@ fn parse_point(raw: &str) -> anyhow::Result<(DateTime<Utc>, bool)> {
     Ok((Utc.from_utc_datetime(&datetime), true))
 }
 
+/// Options d'import CSV des shifts.
+#[derive(Debug, Clone)]
+pub struct ShiftImportOptions {
+    /// Séparateur de champs (`;` pour les exports Excel français).
+    pub delimiter: u8,
+    /// Fuseau des dates locales, sauf colonne `timezone` renseignée.
+    pub zone: Zone,
+    /// Colonne -> en-tête du fichier, pour les en-têtes non standard
+    /// (ex. `start` -> `Début`).
+    pub columns: BTreeMap<String, String>,
+    /// Équipe des shifts créés, prise en compte dans la détection des doublons.
+    pub team: Option<String>,
+    /// Avec [`import_shifts_into`], importe les lignes valides et rejette
+    /// les autres au lieu de tout refuser.
+    pub skip_invalid: bool,
+}
+
+impl Default for ShiftImportOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            zone: Zone::Utc,
+            columns: BTreeMap::new(),
+            team: None,
+            skip_invalid: false,
+        }
+    }
+}
+
+/// Colonnes reconnues à l'import des shifts (`parent_id` et `override`
+/// proviennent de [`export_shifts_csv_with`]).
+pub const SHIFT_COLUMNS: [&str; 9] = [
+    "id",
+    "name",
+    "start",
+    "end",
+    "role",
+    "assigned_handle",
+    "timezone",
+    "parent_id",
+    "override",
+];
+
+/// Position des colonnes, d'après l'en-tête et le mapping.
+struct ShiftColumns(BTreeMap<&'static str, usize>);
+
+impl ShiftColumns {
+    fn new(headers: &csv::StringRecord, opts: &ShiftImportOptions) -> anyhow::Result<Self> {
+        for column in opts.columns.keys() {
+            if !SHIFT_COLUMNS.contains(&column.as_str()) {
+                bail!("unknown shift column in mapping: {column}");
+            }
+        }
+        let mut found = BTreeMap::new();
+        for column in SHIFT_COLUMNS {
+            let header = opts.columns.get(column).map_or(column, String::as_str);
+            let pos = headers
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(header.trim()));
+            match pos {
+                Some(pos) => {
+                    found.insert(column, pos);
+                }
+                None if opts.columns.contains_key(column) => {
+                    bail!("mapped column not found in header: {header}");
+                }
+                None => {}
+            }
+        }
+        // compatibilité : `name,start,end` par position si l'en-tête n'en nomme aucune
+        const REQUIRED: [&str; 3] = ["name", "start", "end"];
+        if !REQUIRED.iter().any(|c| found.contains_key(c)) {
+            found.extend(REQUIRED.into_iter().zip(0..));
+        }
+        Ok(Self(found))
+    }
+
+    fn has(&self, column: &str) -> bool {
+        self.0.contains_key(column)
+    }
+
+    fn get<'r>(&self, rec: &'r csv::StringRecord, column: &str) -> Option<&'r str> {
+        let pos = *self.0.get(column)?;
+        rec.get(pos).map(str::trim).filter(|v| !v.is_empty())
+    }
+}
+
+/// Date/heure de shift : RFC3339 (décalage explicite), sinon heure locale
+/// `YYYY-MM-DD HH:MM[:SS]`, `YYYY-MM-DDTHH:MM[:SS]` ou `DD/MM/YYYY HH:MM[:SS]`
+/// dans `zone`. Une date seule `YYYY-MM-DD` (cellule date d'un tableur) vaut minuit.
+pub fn parse_local_datetime(raw: &str, zone: Zone) -> anyhow::Result<DateTime<Utc>> {
+    let raw = raw.trim();
+    if let Ok(dt) = raw.parse::<DateTime<Utc>>() {
+        return Ok(dt);
+    }
+    const FORMATS: [&str; 6] = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%d/%m/%Y %H:%M:%S",
+        "%d/%m/%Y %H:%M",
+    ];
+    let naive = FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(raw, f).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+        .with_context(|| format!("invalid datetime: {raw}"))?;
+    zone.from_local(naive)
+        .with_context(|| format!("nonexistent local time in {zone}: {raw}"))
+}
+
 /// Import de shifts: header `name,start,end` (RFC3339 UTC)
 pub fn import_shifts_csv<P: AsRef<Path>>(path: P) -> anyhow::Result<Vec<Shift>> {
+    import_shifts_csv_with(path, &Roster::default(), &ShiftImportOptions::default())
+}
+
+/// Import de shifts par en-tête : `name,start,end` et, optionnellement,
+/// `id`, `role`, `assigned_handle` (personne connue de `roster`) et
+/// `timezone` (fuseau des dates locales de la ligne). Toutes les lignes sont
+/// validées ; la moindre erreur fait échouer l'import ([`ImportErrors`]).
+pub fn import_shifts_csv_with<P: AsRef<Path>>(
+    path: P,
+    roster: &Roster,
+    opts: &ShiftImportOptions,
+) -> anyhow::Result<Vec<Shift>> {
+    let (headers, rows) = read_rows(path.as_ref(), opts.delimiter)?;
+    let columns = ShiftColumns::new(&headers, opts)?;
+    let mut out: Vec<(u64, Shift)> = Vec::new();
+    let mut errors = Vec::new();
+    for (line, rec) in rows {
+        let shift = match shift_from_record(&rec, &columns, roster, opts) {
+            Ok(shift) => shift,
+            Err(e) => {
+                errors.extend(e.into_iter().map(|e| e.at(line)));
+                continue;
+            }
+        };
+        if roster.find_shift(&shift.id).is_some() || out.iter().any(|(_, s)| s.id == shift.id) {
+            let e = FieldError::new("id", format!("duplicate shift id {}", shift.id.as_str()));
+            errors.push(e.at(line));
+        } else if let Some((first, _)) = out.iter().find(|(_, s)| same_slot(s, &shift)) {
+            errors.push(FieldError::row(format!("duplicate of line {first}")).at(line));
+        } else {
+            out.push((line, shift));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(ImportErrors(errors).into());
+    }
+    Ok(out.into_iter().map(|(_, s)| s).collect())
+}
+
+fn shift_from_record(
+    rec: &csv::StringRecord,
+    columns: &ShiftColumns,
+    roster: &Roster,
+    opts: &ShiftImportOptions,
+) -> Result<Shift, Vec<FieldError>> {
+    let mut errors = Vec::new();
+    let required = |errors: &mut Vec<FieldError>, column| {
+        let value = columns.get(rec, column);
+        if value.is_none() {
+            errors.push(FieldError::new(column, "missing value"));
+        }
+        value
+    };
+    let name = required(&mut errors, "name");
+    let zone = match columns.get(rec, "timezone") {
+        Some(tz) => check_field(&mut errors, "timezone", tz.parse::<Zone>()).unwrap_or(opts.zone),
+        None => opts.zone,
+    };
+    let start = required(&mut errors, "start")
+        .and_then(|raw| check_field(&mut errors, "start", parse_local_datetime(raw, zone)));
+    let end = required(&mut errors, "end")
+        .and_then(|raw| check_field(&mut errors, "end", parse_local_datetime(raw, zone)));
+    let role = columns
+        .get(rec, "role")
+        .and_then(|raw| check_field(&mut errors, "role", raw.parse::<Role>()));
+    let assigned = columns.get(rec, "assigned_handle").and_then(|handle| {
+        let person = roster
+            .find_person_by_handle(handle)
+            .map(|p| p.id.clone())
+            .with_context(|| format!("unknown handle: {handle}"));
+        check_field(&mut errors, "assigned_handle", person)
+    });
+    let (Some(name), Some(start), Some(end)) = (name, start, end) else {
+        return Err(errors);
+    };
+    let mut shift = match Shift::new(name.to_string(), start, end, None) {
+        Ok(shift) => shift,
+        Err(e) => {
+            errors.push(FieldError::new("end", e));
+            return Err(errors);
+        }
+    };
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    if let Some(id) = columns.get(rec, "id") {
+        shift.id = ShiftId::new(id);
+    }
+    shift.role = role;
+    shift.assigned = assigned;
+    shift.team = opts.team.clone();
+    Ok(shift)
+}
+
+/// Même créneau : nom, horaires, rôle, équipe et shift parent identiques.
+fn same_slot(a: &Shift, b: &Shift) -> bool {
+    (&a.name, a.start, a.end, &a.role, &a.team, &a.parent)
+        == (&b.name, b.start, b.end, &b.role, &b.team, &b.parent)
+}
+
+/// Import d'interventions: header `handle,start,end` (RFC3339 UTC)
+pub fn import_interventions_csv<P: AsRef<Path>>(path: P) -> anyhow::Result<Vec<Intervention>> {
     let mut rdr = ReaderBuilder::new().has_headers(true).from_path(path)?;
     let mut out = Vec::new();
     for rec in rdr.records() {
         let rec = rec?;
-        let name = rec.get(0).context("missing name")?.trim().to_string();
+        let handle = rec.get(0).context("missing handle")?.trim().to_string();
         let start = rec.get(1).context("missing start")?.trim();
         let end = rec.get(2).context("missing end")?.trim();
         let start: DateTime<Utc> = start.parse().context("start RFC3339")?;
         let end: DateTime<Utc> = end.parse().context("end RFC3339")?;
-        let s = Shift::new(name, start, end, None).map_err(anyhow::Error::msg)?;
-        out.push(s);
+        if handle.is_empty() {
+            bail!("invalid intervention row (empty handle)");
+        }
+        if end <= start {
+            bail!("intervention end must be after start for handle {handle}");
+        }
+        out.push(Intervention { handle, start, end });
     }
     Ok(out)
 }
@@ -102,27 +558,1223 @@ pub fn export_roster_json<P: AsRef<Path>>(path: P, roster: &Roster) -> anyhow::R
     Ok(())
 }
 
-/// Export CSV des shifts: header `id,name,start,end,assigned_handle`
+/// Résultat d'un import de shifts par `id`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShiftDiff {
+    pub added: Vec<ShiftId>,
+    /// Shift et champs modifiés.
+    pub updated: Vec<(ShiftId, Vec<&'static str>)>,
+    pub unchanged: Vec<ShiftId>,
+    /// Lignes ignorées avec [`ShiftImportOptions::skip_invalid`].
+    pub rejected: Vec<RowError>,
+}
+
+impl ShiftDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty()
+    }
+}
+
+/// Lignes d'un même shift : segments de l'export regroupés sous leur `id`.
+struct ShiftGroup {
+    line: u64,
+    shift: Shift,
+    overrides: Vec<Override>,
+}
+
+/// Import de shifts mettant à jour `roster` : reconnaît le format de
+/// [`export_shifts_csv`] (une ligne par shift) et les lignes `override` de
+/// [`export_shifts_csv_with`]. Un `id` connu met le shift à jour, seuls les
+/// champs présents dans le fichier étant comparés ; les autres lignes créent
+/// des shifts. Toutes les lignes sont validées avant la moindre modification :
+/// sans [`ShiftImportOptions::skip_invalid`], une seule erreur fait tout
+/// refuser ([`ImportErrors`]).
+pub fn import_shifts_into<P: AsRef<Path>>(
+    path: P,
+    roster: &mut Roster,
+    opts: &ShiftImportOptions,
+) -> anyhow::Result<ShiftDiff> {
+    apply_shift_rows(read_rows(path.as_ref(), opts.delimiter)?, roster, opts)
+}
+
+fn apply_shift_rows(
+    rows: Rows,
+    roster: &mut Roster,
+    opts: &ShiftImportOptions,
+) -> anyhow::Result<ShiftDiff> {
+    let (columns, groups, rejected) = read_shift_groups(rows, roster, opts)?;
+    if !rejected.is_empty() && !opts.skip_invalid {
+        return Err(ImportErrors(rejected).into());
+    }
+
+    let mut diff = ShiftDiff {
+        rejected,
+        ..ShiftDiff::default()
+    };
+    for group in groups {
+        let id = group.shift.id.clone();
+        let Some(current) = roster.find_shift_mut(&id) else {
+            roster.shifts.push(group.shift);
+            roster.overrides.extend(group.overrides);
+            diff.added.push(id);
+            continue;
+        };
+        let incoming = group.shift;
+        let mut fields = Vec::new();
+        if current.name != incoming.name {
+            current.name = incoming.name;
+            fields.push("name");
+        }
+        if (current.start, current.end) != (incoming.start, incoming.end) {
+            current.start = incoming.start;
+            current.end = incoming.end;
+            fields.push("time");
+        }
+        if columns.has("role") && current.role != incoming.role {
+            current.role = incoming.role;
+            fields.push("role");
+        }
+        if columns.has("assigned_handle") && current.assigned != incoming.assigned {
+            current.assigned = incoming.assigned;
+            fields.push("assigned");
+        }
+        if columns.has("parent_id") && current.parent != incoming.parent {
+            current.parent = incoming.parent;
+            fields.push("parent");
+        }
+        if columns.has("override") {
+            let mut existing: Vec<&Override> = roster.overrides_for(&id).collect();
+            existing.sort_by_key(|o| o.start);
+            let mut incoming: Vec<&Override> = group.overrides.iter().collect();
+            incoming.sort_by_key(|o| o.start);
+            if existing != incoming {
+                roster.overrides.retain(|o| o.shift_id != id);
+                roster.overrides.extend(group.overrides);
+                fields.push("overrides");
+            }
+        }
+        if fields.is_empty() {
+            diff.unchanged.push(id);
+        } else {
+            diff.updated.push((id, fields));
+        }
+    }
+    Ok(diff)
+}
+
+/// Valide les lignes d'un fichier de shifts : groupes valides par `id`, dans
+/// l'ordre du fichier, et erreurs triées par ligne. Une ligne invalide
+/// écarte tout le shift auquel elle appartient.
+fn read_shift_groups(
+    (headers, records): Rows,
+    roster: &Roster,
+    opts: &ShiftImportOptions,
+) -> anyhow::Result<(ShiftColumns, Vec<ShiftGroup>, Vec<RowError>)> {
+    let columns = ShiftColumns::new(&headers, opts)?;
+    let mut errors = Vec::new();
+    let mut invalid: HashSet<ShiftId> = HashSet::new();
+    let mut rows: Vec<(u64, Shift, bool)> = Vec::new();
+    for (line, rec) in records {
+        match segment_from_record(&rec, &columns, roster, opts) {
+            Ok((shift, is_override)) => rows.push((line, shift, is_override)),
+            Err(e) => {
+                if let Some(id) = columns.get(&rec, "id") {
+                    invalid.insert(ShiftId::new(id));
+                }
+                errors.extend(e.into_iter().map(|e| e.at(line)));
+            }
+        }
+    }
+    let mut groups: Vec<ShiftGroup> = Vec::new();
+    // segments de base d'abord : un remplacement peut précéder sa ligne de base
+    rows.sort_by_key(|(_, _, is_override)| *is_override);
+    for (line, row, is_override) in rows {
+        let id = row.id.clone();
+        if let Err(e) = add_segment(&mut groups, line, row, is_override) {
+            invalid.insert(id);
+            errors.push(e.at(line));
+        }
+    }
+    groups.retain(|g| !invalid.contains(&g.shift.id));
+    let ids: HashSet<ShiftId> = groups.iter().map(|g| g.shift.id.clone()).collect();
+    let mut valid: Vec<ShiftGroup> = Vec::new();
+    for group in groups {
+        let checked = check_group(&group, &columns, roster, &ids)
+            .and_then(|()| check_duplicate(&group, roster, &ids, &valid));
+        match checked {
+            Ok(()) => valid.push(group),
+            Err(e) => errors.push(e.at(group.line)),
+        }
+    }
+    errors.sort_by_key(|e| e.line);
+    Ok((columns, valid, errors))
+}
+
+/// Segment lu sur une ligne, et s'il s'agit d'un remplacement ponctuel.
+fn segment_from_record(
+    rec: &csv::StringRecord,
+    columns: &ShiftColumns,
+    roster: &Roster,
+    opts: &ShiftImportOptions,
+) -> Result<(Shift, bool), Vec<FieldError>> {
+    let mut errors = Vec::new();
+    let is_override = match columns.get(rec, "override") {
+        Some(flag) => check_field(&mut errors, "override", parse_bool(flag)).unwrap_or(false),
+        None => false,
+    };
+    if is_override && columns.get(rec, "id").is_none() {
+        errors.push(FieldError::new("id", "override row without id"));
+    }
+    match shift_from_record(rec, columns, roster, opts) {
+        Ok(mut row) if errors.is_empty() => {
+            row.parent = columns.get(rec, "parent_id").map(ShiftId::new);
+            Ok((row, is_override))
+        }
+        Ok(_) => Err(errors),
+        Err(mut row_errors) => {
+            row_errors.append(&mut errors);
+            Err(row_errors)
+        }
+    }
+}
+
+/// Rattache un segment à son shift : nouveau groupe, ou bornes élargies
+/// d'un groupe de même `id`.
+fn add_segment(
+    groups: &mut Vec<ShiftGroup>,
+    line: u64,
+    row: Shift,
+    is_override: bool,
+) -> Result<(), FieldError> {
+    let existing = groups.iter_mut().find(|g| g.shift.id == row.id);
+    let Some(group) = existing else {
+        if is_override {
+            let reason = format!("override row without shift row {}", row.id.as_str());
+            return Err(FieldError::new("id", reason));
+        }
+        groups.push(ShiftGroup {
+            line,
+            shift: row,
+            overrides: Vec::new(),
+        });
+        return Ok(());
+    };
+    group.shift.start = group.shift.start.min(row.start);
+    group.shift.end = group.shift.end.max(row.end);
+    if is_override {
+        let Some(person) = row.assigned else {
+            return Err(FieldError::new(
+                "assigned_handle",
+                "override row without assigned_handle",
+            ));
+        };
+        group.overrides.push(Override {
+            shift_id: row.id,
+            start: row.start,
+            end: row.end,
+            person,
+        });
+    } else if group.shift.assigned != row.assigned {
+        let reason = format!("conflicting assignee for shift {}", row.id.as_str());
+        return Err(FieldError::new("assigned_handle", reason));
+    }
+    Ok(())
+}
+
+fn check_group(
+    group: &ShiftGroup,
+    columns: &ShiftColumns,
+    roster: &Roster,
+    ids: &HashSet<ShiftId>,
+) -> Result<(), FieldError> {
+    let shift = &group.shift;
+    if let Some(parent) = &shift.parent {
+        if roster.find_shift(parent).is_none() && !ids.contains(parent) {
+            let reason = format!("unknown parent_id {}", parent.as_str());
+            return Err(FieldError::new("parent_id", reason));
+        }
+    }
+    for o in &group.overrides {
+        if o.start < shift.start || o.end > shift.end {
+            let reason = format!("override outside shift {}", shift.id.as_str());
+            return Err(FieldError::new("override", reason));
+        }
+        if Some(&o.person) == shift.assigned.as_ref() {
+            return Err(FieldError::new(
+                "override",
+                "override held by the shift assignee",
+            ));
+        }
+    }
+    if let Some(current) = roster.find_shift(&shift.id) {
+        let reassigned = columns.has("assigned_handle") && current.assigned != shift.assigned;
+        let moved = (current.start, current.end) != (shift.start, shift.end);
+        if current.locked && (reassigned || moved) {
+            return Err(FieldError::row(format!(
+                "shift {} locked",
+                shift.id.as_str()
+            )));
+        }
+        // sans colonne `override`, les remplacements du roster sont conservés :
+        // ils doivent rester valides sur le shift modifié
+        if !columns.has("override") {
+            for o in roster.overrides_for(&shift.id) {
+                if o.start < shift.start || o.end > shift.end {
+                    let reason = format!(
+                        "existing override {} - {} outside the new time range",
+                        o.start.to_rfc3339(),
+                        o.end.to_rfc3339()
+                    );
+                    return Err(FieldError::row(reason));
+                }
+                if Some(&o.person) == shift.assigned.as_ref() {
+                    return Err(FieldError::new(
+                        "assigned_handle",
+                        "assignee already holds an override on this shift",
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Rejette un shift occupant le même créneau qu'un shift déjà lu plus haut,
+/// ou qu'un shift du roster absent du fichier.
+fn check_duplicate(
+    group: &ShiftGroup,
+    roster: &Roster,
+    ids: &HashSet<ShiftId>,
+    earlier: &[ShiftGroup],
+) -> Result<(), FieldError> {
+    let shift = &group.shift;
+    if let Some(first) = earlier.iter().find(|g| same_slot(&g.shift, shift)) {
+        return Err(FieldError::row(format!("duplicate of line {}", first.line)));
+    }
+    let existing = roster
+        .shifts
+        .iter()
+        .filter(|s| !ids.contains(&s.id))
+        .find(|s| same_slot(s, shift));
+    if let Some(existing) = existing {
+        let reason = format!("duplicate of existing shift {}", existing.id.as_str());
+        return Err(FieldError::row(reason));
+    }
+    Ok(())
+}
+
+/// Export CSV des shifts, une ligne par shift : header
+/// `id,name,start,end,assigned_handle,parent_id,role`. `parent_id` relie les
+/// segments de cover à leur shift logique. Relisible par [`import_shifts_into`].
 pub fn export_shifts_csv<P: AsRef<Path>>(path: P, roster: &Roster) -> anyhow::Result<()> {
+    export_shifts_csv_with(path, roster, false)
+}
+
+/// Comme [`export_shifts_csv`] ; avec `overrides`, une colonne `override` est
+/// ajoutée et chaque remplacement ponctuel suit la ligne de son shift, sous le
+/// même id (`override` à `true`, titulaire dans `assigned_handle`).
+pub fn export_shifts_csv_with<P: AsRef<Path>>(
+    path: P,
+    roster: &Roster,
+    overrides: bool,
+) -> anyhow::Result<()> {
     let mut w = WriterBuilder::new().has_headers(true).from_path(path)?;
-    w.write_record(["id", "name", "start", "end", "assigned_handle"])?;
+    let mut headers = vec![
+        "id",
+        "name",
+        "start",
+        "end",
+        "assigned_handle",
+        "parent_id",
+        "role",
+    ];
+    if overrides {
+        headers.push("override");
+    }
+    w.write_record(&headers)?;
+    let handle = |pid: Option<&PersonId>| {
+        pid.and_then(|pid| roster.find_person_by_id(pid))
+            .map_or("", |p| p.handle.as_str())
+    };
     for s in &roster.shifts {
-        let assigned = s
-            .assigned
-            .as_ref()
-            .and_then(|pid| roster.people.iter().find(|p| p.id == *pid))
-            .map(|p| p.handle.as_str())
-            .unwrap_or("");
-        let start = s.start.to_rfc3339();
-        let end = s.end.to_rfc3339();
+        let role = s.role.as_ref().map(ToString::to_string).unwrap_or_default();
+        let parent = s.parent.as_ref().map_or("", |p| p.as_str());
+        let mut rows = vec![(s.start, s.end, handle(s.assigned.as_ref()), "")];
+        if overrides {
+            rows.extend(
+                roster
+                    .overrides_for(&s.id)
+                    .map(|o| (o.start, o.end, handle(Some(&o.person)), "true")),
+            );
+        }
+        for (start, end, assigned, flag) in rows {
+            let start = start.to_rfc3339();
+            let end = end.to_rfc3339();
+            let mut record = vec![
+                s.id.as_str(),
+                s.name.as_str(),
+                start.as_str(),
+                end.as_str(),
+                assigned,
+                parent,
+                role.as_str(),
+            ];
+            if overrides {
+                record.push(flag);
+            }
+            w.write_record(&record)?;
+        }
+    }
+    w.flush()?;
+    Ok(())
+}
+
+/// Grille de planning lisible : une ligne par jour, une colonne par rôle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanningGrid {
+    /// Colonnes dans l'ordre d'apparition, `primary` en tête.
+    pub roles: Vec<Role>,
+    pub days: Vec<PlanningDay>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanningDay {
+    pub date: NaiveDate,
+    pub weekend: bool,
+    /// Nom du jour férié, le cas échéant.
+    pub holiday: Option<String>,
+    /// Noms affichés des personnes d'astreinte, par rôle (ordre de `roles`).
+    pub cells: Vec<Vec<String>>,
+}
+
+/// Grille des `days` jours à partir de `from` (dates locales de `zone`) :
+/// chaque cellule liste les personnes tenant un shift du rôle ce jour-là,
+/// remplacements ponctuels compris. Un shift sans rôle compte comme `primary` ;
+/// `team` restreint la grille aux shifts de l'équipe.
+pub fn planning_grid(
+    roster: &Roster,
+    from: NaiveDate,
+    days: u32,
+    zone: Zone,
+    holidays: &HolidayCalendar,
+    team: Option<&str>,
+) -> PlanningGrid {
+    let mut grid = PlanningGrid {
+        roles: vec![Role::Primary],
+        days: from
+            .iter_days()
+            .take(days as usize)
+            .map(|date| PlanningDay {
+                date,
+                weekend: matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
+                holiday: holidays.name(date).map(str::to_string),
+                cells: Vec::new(),
+            })
+            .collect(),
+    };
+    let Some(last) = grid.days.last().map(|d| d.date) else {
+        return grid;
+    };
+    let mut shifts: Vec<&Shift> = roster
+        .shifts
+        .iter()
+        .filter(|s| team.map_or(true, |t| s.team.as_deref() == Some(t)))
+        .filter(|s| zone.naive_local(s.end).date() >= from)
+        .filter(|s| zone.naive_local(s.start).date() <= last)
+        .collect();
+    shifts.sort_by_key(|s| s.start);
+    for shift in shifts {
+        let role = shift.role.clone().unwrap_or(Role::Primary);
+        for segment in roster.segments(shift) {
+            let Some(person) = segment.holder.and_then(|pid| roster.find_person_by_id(pid)) else {
+                continue;
+            };
+            for (date, _, _) in zone.split_days(segment.start, segment.end) {
+                if date < from || date > last {
+                    continue;
+                }
+                let col = match grid.roles.iter().position(|r| *r == role) {
+                    Some(col) => col,
+                    None => {
+                        grid.roles.push(role.clone());
+                        grid.roles.len() - 1
+                    }
+                };
+                let day = &mut grid.days[(date - from).num_days() as usize];
+                day.cells.resize(grid.roles.len(), Vec::new());
+                if !day.cells[col].contains(&person.display_name) {
+                    day.cells[col].push(person.display_name.clone());
+                }
+            }
+        }
+    }
+    for day in &mut grid.days {
+        day.cells.resize(grid.roles.len(), Vec::new());
+    }
+    grid
+}
+
+/// Export CSV des statistiques: header `period,handle,display_name,shifts,hours,...`
+pub fn export_stats_csv<P: AsRef<Path>>(path: P, stats: &[PersonStats]) -> anyhow::Result<()> {
+    let mut w = WriterBuilder::new().has_headers(true).from_path(path)?;
+    w.write_record([
+        "period",
+        "handle",
+        "display_name",
+        "shifts",
+        "hours",
+        "night_hours",
+        "weekend_shifts",
+        "holiday_shifts",
+        "covers_given",
+        "covers_received",
+        "swaps",
+        "hours_deviation",
+        "shifts_deviation",
+    ])?;
+    for r in stats {
         w.write_record([
-            s.id.as_str(),
-            s.name.as_str(),
-            start.as_str(),
-            end.as_str(),
-            assigned,
+            r.period.clone(),
+            r.handle.clone(),
+            r.display_name.clone(),
+            r.shifts.to_string(),
+            format!("{:.2}", r.hours),
+            format!("{:.2}", r.night_hours),
+            r.weekend_shifts.to_string(),
+            r.holiday_shifts.to_string(),
+            r.covers_given.to_string(),
+            r.covers_received.to_string(),
+            r.swaps.to_string(),
+            format!("{:.2}", r.hours_deviation),
+            format!("{:.2}", r.shifts_deviation),
         ])?;
     }
     w.flush()?;
     Ok(())
 }
+
+/// Export JSON des statistiques (jolie mise en forme)
+pub fn export_stats_json<P: AsRef<Path>>(path: P, stats: &[PersonStats]) -> anyhow::Result<()> {
+    let s = serde_json::to_string_pretty(stats)?;
+    fs::write(path, s)?;
+    Ok(())
+}
+
+/// Export CSV de paie: header `month,handle,display_name,weekday_hours,weekend_hours,holiday_hours,intervention_hours,amount`
+pub fn export_payroll_csv<P: AsRef<Path>>(path: P, lines: &[PayrollLine]) -> anyhow::Result<()> {
+    let mut w = WriterBuilder::new().has_headers(true).from_path(path)?;
+    w.write_record([
+        "month",
+        "handle",
+        "display_name",
+        "weekday_hours",
+        "weekend_hours",
+        "holiday_hours",
+        "intervention_hours",
+        "amount",
+    ])?;
+    for l in lines {
+        w.write_record([
+            l.month.clone(),
+            l.handle.clone(),
+            l.display_name.clone(),
+            format!("{:.2}", l.weekday_hours),
+            format!("{:.2}", l.weekend_hours),
+            format!("{:.2}", l.holiday_hours),
+            format!("{:.2}", l.intervention_hours),
+            format!("{:.2}", l.amount),
+        ])?;
+    }
+    w.flush()?;
+    Ok(())
+}
+
+/// Format de planning d'un outil d'alerting tiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleFormat {
+    PagerDuty,
+    Opsgenie,
+}
+
+impl FromStr for ScheduleFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "pagerduty" | "pd" => Ok(ScheduleFormat::PagerDuty),
+            "opsgenie" | "og" => Ok(ScheduleFormat::Opsgenie),
+            _ => Err(format!("unknown schedule format: {s}")),
+        }
+    }
+}
+
+/// Couche (layer/rotation) des shifts sans équipe.
+const DEFAULT_LAYER: &str = "default";
+
+fn layer_name(shift: &Shift) -> &str {
+    shift.team.as_deref().unwrap_or(DEFAULT_LAYER)
+}
+
+/// Shifts assignés regroupés par couche, dans l'ordre d'apparition.
+fn layers(roster: &Roster) -> Vec<(&str, Vec<&Shift>)> {
+    let mut out: Vec<(&str, Vec<&Shift>)> = Vec::new();
+    let mut shifts: Vec<&Shift> = roster
+        .shifts
+        .iter()
+        .filter(|s| s.assigned.is_some())
+        .collect();
+    shifts.sort_by_key(|s| s.start);
+    for s in shifts {
+        match out.iter_mut().find(|(name, _)| *name == layer_name(s)) {
+            Some((_, group)) => group.push(s),
+            None => out.push((layer_name(s), vec![s])),
+        }
+    }
+    out
+}
+
+/// Handle déduit d'un identifiant externe : partie locale d'un e-mail,
+/// sinon nom en minuscules, espaces remplacés par des points.
+fn external_handle(raw: &str) -> String {
+    let local = raw.split('@').next().unwrap_or(raw).trim();
+    local
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(".")
+        .to_lowercase()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PdDocument {
+    schedule: PdSchedule,
+    #[serde(default)]
+    overrides: Vec<PdOverride>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PdSchedule {
+    name: String,
+    #[serde(default)]
+    time_zone: String,
+    #[serde(default)]
+    schedule_layers: Vec<PdLayer>,
+    #[serde(default)]
+    users: Vec<PdUser>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    final_schedule: Option<PdRendered>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PdLayer {
+    name: String,
+    start: DateTime<Utc>,
+    #[serde(default)]
+    end: Option<DateTime<Utc>>,
+    #[serde(default)]
+    users: Vec<PdLayerUser>,
+    #[serde(default)]
+    rendered_schedule_entries: Vec<PdEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PdLayerUser {
+    user: PdUser,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PdUser {
+    id: String,
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PdEntry {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    user: PdUser,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PdRendered {
+    name: String,
+    rendered_schedule_entries: Vec<PdEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PdOverride {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    user: PdUser,
+}
+
+impl PdUser {
+    fn from_person(person: &Person) -> Self {
+        PdUser {
+            id: person.id.as_str().to_string(),
+            kind: "user_reference".into(),
+            summary: person.display_name.clone(),
+            email: None,
+        }
+    }
+
+    fn to_person(&self) -> Person {
+        let handle = match &self.email {
+            Some(email) => external_handle(email),
+            None if !self.summary.is_empty() => external_handle(&self.summary),
+            None => self.id.clone(),
+        };
+        let display = if self.summary.is_empty() {
+            handle.clone()
+        } else {
+            self.summary.clone()
+        };
+        let mut person = Person::new(handle, display);
+        person.id = PersonId::new(&self.id);
+        person
+    }
+}
+
+fn holder<'a>(roster: &'a Roster, pid: Option<&PersonId>) -> Option<&'a Person> {
+    pid.and_then(|pid| roster.find_person_by_id(pid))
+}
+
+/// Export au format des plannings PagerDuty : `schedule` (couches par équipe,
+/// `final_schedule` remplacements compris) et `overrides`, comme les renvoient
+/// `GET /schedules/{id}` et `GET /schedules/{id}/overrides`.
+pub fn export_pagerduty_json<P: AsRef<Path>>(
+    path: P,
+    roster: &Roster,
+    name: &str,
+) -> anyhow::Result<()> {
+    let mut users: Vec<PdUser> = Vec::new();
+    let mut user = |person: &Person| {
+        let u = PdUser::from_person(person);
+        if !users.iter().any(|x| x.id == u.id) {
+            users.push(u.clone());
+        }
+        u
+    };
+    let mut schedule_layers = Vec::new();
+    for (layer, shifts) in layers(roster) {
+        let mut layer_users: Vec<PdLayerUser> = Vec::new();
+        let mut entries = Vec::new();
+        for s in &shifts {
+            let Some(person) = holder(roster, s.assigned.as_ref()) else {
+                continue;
+            };
+            let u = user(person);
+            if !layer_users.iter().any(|lu| lu.user.id == u.id) {
+                layer_users.push(PdLayerUser { user: u.clone() });
+            }
+            entries.push(PdEntry {
+                start: s.start,
+                end: s.end,
+                user: u,
+            });
+        }
+        schedule_layers.push(PdLayer {
+            name: layer.to_string(),
+            start: shifts[0].start,
+            end: shifts.iter().map(|s| s.end).max(),
+            users: layer_users,
+            rendered_schedule_entries: entries,
+        });
+    }
+    let mut final_entries = Vec::new();
+    for s in &roster.shifts {
+        for segment in roster.segments(s) {
+            if let Some(person) = holder(roster, segment.holder) {
+                final_entries.push(PdEntry {
+                    start: segment.start,
+                    end: segment.end,
+                    user: user(person),
+                });
+            }
+        }
+    }
+    final_entries.sort_by_key(|e| e.start);
+    let overrides = roster
+        .overrides
+        .iter()
+        // les shifts non assignés ne sont pas exportés, leurs remplacements non plus
+        .filter(|o| {
+            roster
+                .find_shift(&o.shift_id)
+                .is_some_and(|s| s.assigned.is_some())
+        })
+        .filter_map(|o| {
+            Some(PdOverride {
+                start: o.start,
+                end: o.end,
+                user: user(holder(roster, Some(&o.person))?),
+            })
+        })
+        .collect();
+    let doc = PdDocument {
+        schedule: PdSchedule {
+            name: name.to_string(),
+            time_zone: "UTC".into(),
+            schedule_layers,
+            users,
+            final_schedule: Some(PdRendered {
+                name: "Final Schedule".into(),
+                rendered_schedule_entries: final_entries,
+            }),
+        },
+        overrides,
+    };
+    fs::write(path, serde_json::to_string_pretty(&doc)?)?;
+    Ok(())
+}
+
+/// Import d'un planning PagerDuty exporté (voir [`export_pagerduty_json`]) :
+/// une personne par utilisateur, un shift par entrée de couche, les
+/// remplacements découpés sur les shifts qu'ils recouvrent.
+pub fn import_pagerduty_json<P: AsRef<Path>>(path: P) -> anyhow::Result<Roster> {
+    let path = path.as_ref();
+    let data = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let doc: PdDocument =
+        serde_json::from_slice(&data).with_context(|| "parsing PagerDuty schedule")?;
+    let mut roster = Roster::default();
+    let add_user = |roster: &mut Roster, u: &PdUser| add_external(roster, u.to_person());
+    for u in &doc.schedule.users {
+        add_user(&mut roster, u);
+    }
+    for layer in &doc.schedule.schedule_layers {
+        for entry in &layer.rendered_schedule_entries {
+            let mut shift = Shift::new(layer.name.clone(), entry.start, entry.end, None)
+                .map_err(anyhow::Error::msg)
+                .with_context(|| format!("invalid entry in layer {}", layer.name))?;
+            shift.assigned = Some(add_user(&mut roster, &entry.user));
+            roster.shifts.push(shift);
+        }
+    }
+    for o in &doc.overrides {
+        let person = add_user(&mut roster, &o.user);
+        spread_override(&mut roster, o.start, o.end, &person, &[])?;
+    }
+    Ok(roster)
+}
+
+/// Ajoute une personne importée, sauf si son identifiant ou son handle est déjà connu.
+fn add_external(roster: &mut Roster, person: Person) -> PersonId {
+    let existing = roster
+        .people
+        .iter()
+        .find(|p| p.id == person.id || p.handle == person.handle);
+    match existing {
+        Some(p) => p.id.clone(),
+        None => {
+            let id = person.id.clone();
+            roster.people.push(person);
+            id
+        }
+    }
+}
+
+/// Découpe un remplacement externe sur les shifts qu'il recouvre, limités
+/// aux couches `rotations` si elle n'est pas vide (les shifts importés portent
+/// le nom de leur couche). Un remplacement ne recouvrant aucun shift est une
+/// erreur plutôt que d'être perdu.
+fn spread_override(
+    roster: &mut Roster,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    person: &PersonId,
+    rotations: &[&str],
+) -> anyhow::Result<()> {
+    if end <= start {
+        bail!("override end must be after start");
+    }
+    let covered: Vec<&Shift> = roster
+        .shifts
+        .iter()
+        .filter(|s| s.start < end && start < s.end)
+        .filter(|s| rotations.is_empty() || rotations.contains(&s.name.as_str()))
+        .collect();
+    if covered.is_empty() {
+        bail!(
+            "override {} - {} covers no shift{}",
+            start.to_rfc3339(),
+            end.to_rfc3339(),
+            if rotations.is_empty() {
+                String::new()
+            } else {
+                format!(" of {}", rotations.join(", "))
+            }
+        );
+    }
+    let pieces: Vec<Override> = covered
+        .into_iter()
+        .filter(|s| s.assigned.as_ref() != Some(person))
+        .map(|s| Override {
+            shift_id: s.id.clone(),
+            start: start.max(s.start),
+            end: end.min(s.end),
+            person: person.clone(),
+        })
+        .collect();
+    roster.overrides.extend(pieces);
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OgDocument {
+    data: OgSchedule,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timeline: Option<OgTimeline>,
+    #[serde(default)]
+    overrides: Vec<OgOverride>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OgSchedule {
+    name: String,
+    #[serde(default)]
+    timezone: String,
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    rotations: Vec<OgRotation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OgRotation {
+    name: String,
+    start_date: DateTime<Utc>,
+    #[serde(default)]
+    end_date: Option<DateTime<Utc>>,
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    participants: Vec<OgRecipient>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OgRecipient {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    /// `username` dans les règles, `name` dans la timeline.
+    #[serde(default, alias = "name", skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OgTimeline {
+    #[serde(default)]
+    base_timeline: OgTimelineRotations,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    final_timeline: Option<OgTimelineRotations>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct OgTimelineRotations {
+    #[serde(default)]
+    rotations: Vec<OgTimelineRotation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OgTimelineRotation {
+    name: String,
+    #[serde(default)]
+    periods: Vec<OgPeriod>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OgPeriod {
+    start_date: DateTime<Utc>,
+    end_date: DateTime<Utc>,
+    #[serde(rename = "type", default)]
+    kind: String,
+    recipient: OgRecipient,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OgOverride {
+    #[serde(default)]
+    alias: String,
+    user: OgRecipient,
+    start_date: DateTime<Utc>,
+    end_date: DateTime<Utc>,
+    #[serde(default)]
+    rotations: Vec<OgRotationRef>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OgRotationRef {
+    name: String,
+}
+
+impl OgRecipient {
+    fn from_person(person: &Person) -> Self {
+        OgRecipient {
+            kind: "user".into(),
+            id: Some(person.id.as_str().to_string()),
+            username: Some(person.handle.clone()),
+        }
+    }
+
+    /// Personne désignée ; `None` pour un destinataire autre qu'un utilisateur.
+    fn to_person(&self) -> Option<Person> {
+        if self.kind != "user" {
+            return None;
+        }
+        let username = self.username.as_deref().or(self.id.as_deref())?;
+        let handle = external_handle(username);
+        let mut person = Person::new(handle.clone(), handle);
+        if let Some(id) = &self.id {
+            person.id = PersonId::new(id);
+        }
+        Some(person)
+    }
+}
+
+/// Export au format des plannings Opsgenie : `data` (rotations par équipe),
+/// `timeline` (périodes de base et finales) et `overrides`, comme les renvoient
+/// `GET /schedules/{id}`, `/timeline` et `/overrides`.
+pub fn export_opsgenie_json<P: AsRef<Path>>(
+    path: P,
+    roster: &Roster,
+    name: &str,
+) -> anyhow::Result<()> {
+    let mut rotations = Vec::new();
+    let mut base = Vec::new();
+    let mut fin = Vec::new();
+    for (layer, shifts) in layers(roster) {
+        let mut participants: Vec<OgRecipient> = Vec::new();
+        let mut periods = Vec::new();
+        let mut final_periods = Vec::new();
+        for s in &shifts {
+            let Some(person) = holder(roster, s.assigned.as_ref()) else {
+                continue;
+            };
+            let recipient = OgRecipient::from_person(person);
+            if !participants.iter().any(|p| p.id == recipient.id) {
+                participants.push(recipient.clone());
+            }
+            periods.push(OgPeriod {
+                start_date: s.start,
+                end_date: s.end,
+                kind: "default".into(),
+                recipient,
+            });
+            for segment in roster.segments(s) {
+                if let Some(person) = holder(roster, segment.holder) {
+                    let replaced = segment.holder != s.assigned.as_ref();
+                    final_periods.push(OgPeriod {
+                        start_date: segment.start,
+                        end_date: segment.end,
+                        kind: if replaced { "override" } else { "default" }.into(),
+                        recipient: OgRecipient::from_person(person),
+                    });
+                }
+            }
+        }
+        rotations.push(OgRotation {
+            name: layer.to_string(),
+            start_date: shifts[0].start,
+            end_date: shifts.iter().map(|s| s.end).max(),
+            kind: "custom".into(),
+            participants,
+        });
+        base.push(OgTimelineRotation {
+            name: layer.to_string(),
+            periods,
+        });
+        fin.push(OgTimelineRotation {
+            name: layer.to_string(),
+            periods: final_periods,
+        });
+    }
+    let overrides = roster
+        .overrides
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, o)| {
+            // les shifts non assignés ne sont pas exportés, leurs remplacements non plus
+            let shift = roster
+                .find_shift(&o.shift_id)
+                .filter(|s| s.assigned.is_some())?;
+            Some(OgOverride {
+                alias: format!("{}-{}", o.shift_id.as_str(), idx),
+                user: OgRecipient::from_person(holder(roster, Some(&o.person))?),
+                start_date: o.start,
+                end_date: o.end,
+                rotations: vec![OgRotationRef {
+                    name: layer_name(shift).to_string(),
+                }],
+            })
+        })
+        .collect();
+    let doc = OgDocument {
+        data: OgSchedule {
+            name: name.to_string(),
+            timezone: "UTC".into(),
+            enabled: true,
+            rotations,
+        },
+        timeline: Some(OgTimeline {
+            base_timeline: OgTimelineRotations { rotations: base },
+            final_timeline: Some(OgTimelineRotations { rotations: fin }),
+        }),
+        overrides,
+    };
+    fs::write(path, serde_json::to_string_pretty(&doc)?)?;
+    Ok(())
+}
+
+/// Import d'un planning Opsgenie exporté (voir [`export_opsgenie_json`]) :
+/// un shift par période de la timeline de base (non assigné si le destinataire
+/// n'est pas un utilisateur), les remplacements découpés sur les shifts des
+/// rotations qu'ils visent.
+pub fn import_opsgenie_json<P: AsRef<Path>>(path: P) -> anyhow::Result<Roster> {
+    let path = path.as_ref();
+    let data = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let doc: OgDocument =
+        serde_json::from_slice(&data).with_context(|| "parsing Opsgenie schedule")?;
+    let mut roster = Roster::default();
+    let add = |roster: &mut Roster, r: &OgRecipient| {
+        r.to_person().map(|person| add_external(roster, person))
+    };
+    for rotation in &doc.data.rotations {
+        for r in &rotation.participants {
+            add(&mut roster, r);
+        }
+    }
+    let periods = doc
+        .timeline
+        .iter()
+        .flat_map(|t| &t.base_timeline.rotations)
+        .flat_map(|r| r.periods.iter().map(move |p| (r.name.as_str(), p)));
+    for (name, period) in periods {
+        let mut shift = Shift::new(name.to_string(), period.start_date, period.end_date, None)
+            .map_err(anyhow::Error::msg)
+            .with_context(|| format!("invalid period in rotation {name}"))?;
+        shift.assigned = add(&mut roster, &period.recipient);
+        roster.shifts.push(shift);
+    }
+    for o in &doc.overrides {
+        let person = add(&mut roster, &o.user)
+            .with_context(|| format!("override {} has no user", o.alias))?;
+        let rotations: Vec<&str> = o.rotations.iter().map(|r| r.name.as_str()).collect();
+        spread_override(&mut roster, o.start_date, o.end_date, &person, &rotations)
+            .with_context(|| format!("override {}", o.alias))?;
+    }
+    Ok(roster)
+}
+
+pub fn export_schedule_json<P: AsRef<Path>>(
+    path: P,
+    roster: &Roster,
+    format: ScheduleFormat,
+    name: &str,
+) -> anyhow::Result<()> {
+    match format {
+        ScheduleFormat::PagerDuty => export_pagerduty_json(path, roster, name),
+        ScheduleFormat::Opsgenie => export_opsgenie_json(path, roster, name),
+    }
+}
+
+pub fn import_schedule_json<P: AsRef<Path>>(
+    path: P,
+    format: ScheduleFormat,
+) -> anyhow::Result<Roster> {
+    match format {
+        ScheduleFormat::PagerDuty => import_pagerduty_json(path),
+        ScheduleFormat::Opsgenie => import_opsgenie_json(path),
+    }
+}
+
+/// Bilan de [`merge_schedule`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScheduleImport {
+    pub people_added: Vec<String>,
+    /// Shifts créés.
+    pub shifts: usize,
+    /// Shifts déjà présents (même créneau, même couche), conservés avec leur nom.
+    pub matched: usize,
+    /// Shifts déjà présents dont l'assignation importée diffère.
+    pub reassigned: Vec<ShiftId>,
+    pub overrides: usize,
+}
+
+/// Ajoute un planning importé au roster. Les personnes sont rapprochées par
+/// identifiant puis par handle (l'identifiant existant est conservé). Un
+/// shift de même début, même fin et même couche (équipe, ou `default`) qu'un
+/// shift existant le met à jour au lieu d'en créer un autre : son id et son
+/// nom sont conservés, seule l'assignation suit l'import (sauf verrou). Les
+/// autres shifts d'une couche portant le nom d'une équipe connue lui sont
+/// rattachés. Réimporter le même fichier ne change donc rien.
+pub fn merge_schedule(roster: &mut Roster, imported: Roster) -> ScheduleImport {
+    let mut summary = ScheduleImport::default();
+    let mut ids: Vec<(PersonId, PersonId)> = Vec::new();
+    for person in imported.people {
+        let existing = roster
+            .find_person_by_id(&person.id)
+            .or_else(|| roster.find_person_by_handle(&person.handle))
+            .map(|p| p.id.clone());
+        let id = match existing {
+            Some(id) => id,
+            None => {
+                summary.people_added.push(person.handle.clone());
+                roster.people.push(person.clone());
+                person.id.clone()
+            }
+        };
+        ids.push((person.id, id));
+    }
+    let remap = |pid: &PersonId| {
+        ids.iter()
+            .find(|(from, _)| from == pid)
+            .map_or_else(|| pid.clone(), |(_, to)| to.clone())
+    };
+    // id importé -> id retenu dans le roster
+    let mut shift_ids: Vec<(ShiftId, ShiftId)> = Vec::new();
+    for mut shift in imported.shifts {
+        shift.assigned = shift.assigned.as_ref().map(remap);
+        // les shifts importés portent le nom de leur couche
+        let existing = roster
+            .shifts
+            .iter_mut()
+            .find(|s| (s.start, s.end) == (shift.start, shift.end) && layer_name(s) == shift.name);
+        if let Some(existing) = existing {
+            if !existing.locked && shift.assigned.is_some() && existing.assigned != shift.assigned {
+                existing.assigned = shift.assigned;
+                summary.reassigned.push(existing.id.clone());
+            }
+            shift_ids.push((shift.id, existing.id.clone()));
+            summary.matched += 1;
+            continue;
+        }
+        if roster.find_team(&shift.name).is_some() {
+            shift.team = Some(shift.name.clone());
+        }
+        shift_ids.push((shift.id.clone(), shift.id.clone()));
+        roster.shifts.push(shift);
+        summary.shifts += 1;
+    }
+    for mut o in imported.overrides {
+        o.person = remap(&o.person);
+        if let Some((_, to)) = shift_ids.iter().find(|(from, _)| from == &o.shift_id) {
+            o.shift_id = to.clone();
+        }
+        if roster.overrides.contains(&o) {
+            continue;
+        }
+        roster.overrides.push(o);
+        summary.overrides += 1;
+    }
+    summary
+}
@@ -0,0 +1,93 @@
+//! Classeurs XLSX/ODS (feature `spreadsheet`) : lecture des mêmes colonnes
+//! que les CSV et export de la grille de planning.
+use super::{PlanningGrid, Rows};
+use anyhow::Context;
+use calamine::{open_workbook_auto, Data, Reader};
+use chrono::NaiveTime;
+use rust_xlsxwriter::{Color, Format, FormatBorder, Workbook};
+use std::path::Path;
+
+const WEEKEND_COLOR: u32 = 0xE7E6E6;
+const HOLIDAY_COLOR: u32 = 0xFCE4D6;
+
+/// Première feuille d'un classeur, cellules converties en texte. Les lignes
+/// vides sont ignorées ; les numéros de ligne restent ceux du tableur.
+pub(super) fn read_sheet(path: &Path) -> anyhow::Result<Rows> {
+    let mut workbook =
+        open_workbook_auto(path).with_context(|| format!("cannot open {}", path.display()))?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .with_context(|| format!("{}: workbook without sheet", path.display()))??;
+    let first_line = range.start().map_or(1, |(row, _)| u64::from(row) + 1);
+    let mut rows = range.rows().enumerate().map(|(idx, cells)| {
+        let rec: csv::StringRecord = cells.iter().map(cell_text).collect();
+        (first_line + idx as u64, rec)
+    });
+    let (_, headers) = rows
+        .next()
+        .with_context(|| format!("{}: empty sheet", path.display()))?;
+    let rows = rows
+        .filter(|(_, rec)| rec.iter().any(|v| !v.trim().is_empty()))
+        .collect();
+    Ok((headers, rows))
+}
+
+/// Texte d'une cellule tel que l'attendent les parseurs CSV : dates au format
+/// `YYYY-MM-DD[ HH:MM:SS]`, nombres entiers sans décimale.
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => s.clone(),
+        Data::Float(f) if f.fract() == 0.0 => format!("{f:.0}"),
+        Data::DateTime(dt) => match dt.as_datetime() {
+            Some(naive) if naive.time() == NaiveTime::MIN => naive.format("%Y-%m-%d").to_string(),
+            Some(naive) => naive.format("%Y-%m-%d %H:%M:%S").to_string(),
+            None => dt.to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+/// Export XLSX de la grille (voir [`super::planning_grid`]) : colonnes
+/// `date,day,<rôles>...,holiday`, en-tête figé, week-ends grisés et jours
+/// fériés surlignés avec leur nom.
+pub fn export_planning_xlsx<P: AsRef<Path>>(path: P, grid: &PlanningGrid) -> anyhow::Result<()> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Planning")?;
+
+    let cell = Format::new().set_border(FormatBorder::Thin);
+    let header = cell.clone().set_bold();
+    let weekend = cell.clone().set_background_color(Color::RGB(WEEKEND_COLOR));
+    let holiday = cell.clone().set_background_color(Color::RGB(HOLIDAY_COLOR));
+
+    let mut titles = vec!["date".to_string(), "day".to_string()];
+    titles.extend(grid.roles.iter().map(ToString::to_string));
+    titles.push("holiday".to_string());
+    for (col, title) in titles.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, title, &header)?;
+        sheet.set_column_width(col as u16, if col < 2 { 12 } else { 24 })?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+
+    for (idx, day) in grid.days.iter().enumerate() {
+        let row = idx as u32 + 1;
+        let format = match (&day.holiday, day.weekend) {
+            (Some(_), _) => &holiday,
+            (None, true) => &weekend,
+            (None, false) => &cell,
+        };
+        let mut values = vec![
+            day.date.format("%Y-%m-%d").to_string(),
+            day.date.format("%a").to_string(),
+        ];
+        values.extend(day.cells.iter().map(|names| names.join(", ")));
+        values.push(day.holiday.clone().unwrap_or_default());
+        for (col, value) in values.iter().enumerate() {
+            sheet.write_string_with_format(row, col as u16, value, format)?;
+        }
+    }
+    workbook
+        .save(path.as_ref())
+        .with_context(|| format!("cannot write {}", path.as_ref().display()))
+}
@@ -6,13 +6,34 @@
 //! - Détection de conflits, swaps sûrs.
 //! - Tout en UTC ; parsing RFC3339 ; affichage local en dehors de la lib.
 
+pub mod availability;
+pub mod escalation;
+pub mod holiday;
 pub mod io;
 pub mod model;
 pub mod notification;
+pub mod payroll;
+pub mod query;
 pub mod scheduler;
+pub mod stats;
 pub mod storage;
+pub mod tz;
 
-pub use model::{Person, PersonId, Role, Roster, Shift, ShiftId, VacationPeriod};
+pub use availability::{AvailabilityKind, AvailabilityRule};
+pub use escalation::EscalationStep;
+pub use holiday::HolidayCalendar;
+pub use model::{
+    AuditAction, AuditEntry, EscalationLevel, EscalationPolicy, EscalationTarget, FollowTheSun,
+    Override, PeopleDiff, Person, PersonId, PersonLimits, PersonStatus, Region, Role, Roster,
+    Segment, Shift, ShiftId, SwapRequest, SwapRequestId, SwapStatus, Team, VacationPeriod,
+};
 pub use notification::{prepare_reminder, Reminder, ReminderRenderer, TextReminder};
-pub use scheduler::{AssignOptions, Conflict, ConflictKind, Scheduler};
+pub use payroll::{PayrollLine, PayrollOptions, RateTable};
+pub use query::{OutputFormat, ShiftFilter, ShiftQuery, SortKey};
+pub use scheduler::{
+    AssignOptions, AssignScope, Conflict, ConflictKind, CoverageIssue, CoverageKind,
+    OffboardReport, Scheduler, Transaction,
+};
+pub use stats::{Period, PersonStats, StatsOptions};
 pub use storage::{JsonStorage, Storage};
+pub use tz::Zone;
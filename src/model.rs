@@ -1,5 +1,9 @@
-use chrono::{DateTime, Utc};
+use crate::availability::AvailabilityRule;
+use crate::tz::Zone;
+use chrono::{DateTime, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 use uuid::Uuid;
 
 /// Identifiant fort pour Person
@@ -24,10 +28,25 @@ pub struct Person {
     pub id: PersonId,
     pub handle: String,
     pub display_name: String,
+    /// Membre actif de la rotation ou désactivé (historique conservé).
+    #[serde(default, skip_serializing_if = "PersonStatus::is_active")]
+    pub status: PersonStatus,
+    /// Début de participation à la rotation (UTC).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_from: Option<DateTime<Utc>>,
+    /// Fin de participation à la rotation (UTC, exclue).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_until: Option<DateTime<Utc>>,
     #[serde(default)]
     pub on_vacation: bool,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub vacations: Vec<VacationPeriod>,
+    /// Indisponibilités et préférences récurrentes (heure locale du scheduler).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub availability: Vec<AvailabilityRule>,
+    /// Plafonds propres à la personne (temps partiel, juniors...).
+    #[serde(default, skip_serializing_if = "PersonLimits::is_empty")]
+    pub limits: PersonLimits,
 }
 
 impl Person {
@@ -36,8 +55,102 @@ impl Person {
             id: PersonId::random(),
             handle: handle.into(),
             display_name: display_name.into(),
+            status: PersonStatus::Active,
+            active_from: None,
+            active_until: None,
             on_vacation: false,
             vacations: Vec::new(),
+            availability: Vec::new(),
+            limits: PersonLimits::default(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.status.is_active()
+    }
+
+    /// Vrai si [start, end) tient dans la période de participation.
+    pub fn in_tenure(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        self.active_from.map_or(true, |from| start >= from)
+            && self.active_until.map_or(true, |until| end <= until)
+    }
+}
+
+/// Statut d'un membre dans la rotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PersonStatus {
+    #[default]
+    Active,
+    /// Exclu de l'assignation, conservé pour l'historique.
+    Inactive,
+    /// Parti de l'équipe (offboarding) ; ses shifts passés restent comptés.
+    Archived,
+}
+
+impl PersonStatus {
+    pub fn is_active(&self) -> bool {
+        matches!(self, PersonStatus::Active)
+    }
+}
+
+/// Limites individuelles ; un champ absent laisse la règle globale s'appliquer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PersonLimits {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_shifts_per_month: Option<u32>,
+    /// Heures d'astreinte maximales sur toute fenêtre glissante de 7 jours.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_hours_per_7_days: Option<u32>,
+    /// Week-ends (samedi/dimanche touchés) maximum par trimestre civil.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_weekends_per_quarter: Option<u32>,
+    /// Remplace `AssignOptions::min_rest_hours` pour cette personne.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_rest_hours: Option<u32>,
+    /// Remplace `AssignOptions::max_consecutive_shifts` pour cette personne.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_consecutive_shifts: Option<u32>,
+}
+
+impl PersonLimits {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Options d'assignation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssignOptions {
+    pub min_rest_hours: u32,
+    pub max_consecutive_shifts: u32,
+    /// Sur un shift férié, préférer la personne ayant le moins de fériés.
+    pub balance_holidays: bool,
+    /// Interdire d'attribuer le même férié à une personne deux années de suite.
+    pub holiday_rotation: bool,
+}
+
+impl Default for AssignOptions {
+    fn default() -> Self {
+        Self {
+            min_rest_hours: 11,
+            max_consecutive_shifts: 3,
+            balance_holidays: false,
+            holiday_rotation: false,
+        }
+    }
+}
+
+impl AssignOptions {
+    /// Options effectives pour une personne, limites individuelles appliquées.
+    pub fn for_person(self, limits: &PersonLimits) -> Self {
+        Self {
+            min_rest_hours: limits.min_rest_hours.unwrap_or(self.min_rest_hours),
+            max_consecutive_shifts: limits
+                .max_consecutive_shifts
+                .unwrap_or(self.max_consecutive_shifts),
+            ..self
         }
     }
 }
@@ -66,6 +179,33 @@ pub enum Role {
     Custom(String),
 }
 
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Role::Primary => f.write_str("primary"),
+            Role::Secondary => f.write_str("secondary"),
+            Role::Custom(name) => f.write_str(name),
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    /// `primary` / `secondary` (insensible à la casse), sinon rôle personnalisé.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("empty role".to_string());
+        }
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "primary" => Role::Primary,
+            "secondary" => Role::Secondary,
+            _ => Role::Custom(s.to_string()),
+        })
+    }
+}
+
 /// Identifiant fort pour Shift
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ShiftId(String);
@@ -90,7 +230,20 @@ pub struct Shift {
     pub start: DateTime<Utc>,
     pub end: DateTime<Utc>,
     pub role: Option<Role>,
+    /// Équipe propriétaire du shift ; absente = rotation générale.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
     pub assigned: Option<PersonId>,
+    /// Assignation négociée à la main : ignorée par l'assignation automatique,
+    /// refusée par `swap`/`cover_shift` sauf forçage.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub locked: bool,
+    /// Shift logique d'origine d'un segment créé par `cover_shift`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<ShiftId>,
+    /// Rotation « follow-the-sun » ayant généré le shift.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<String>,
 }
 
 impl Shift {
@@ -110,7 +263,11 @@ impl Shift {
             start,
             end,
             role,
+            team: None,
             assigned: None,
+            locked: false,
+            parent: None,
+            rotation: None,
         })
     }
 
@@ -118,6 +275,253 @@ impl Shift {
     pub fn duration_minutes(&self) -> i64 {
         (self.end - self.start).num_minutes()
     }
+
+    /// Identifiant du shift logique : le parent pour un segment, sinon le shift lui-même.
+    pub fn origin(&self) -> &ShiftId {
+        self.parent.as_ref().unwrap_or(&self.id)
+    }
+
+    /// Vrai si l'instant `at` tombe dans l'intervalle [start, end).
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.start <= at && at < self.end
+    }
+}
+
+/// Résultat d'un import de personnes par handle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeopleDiff {
+    pub added: Vec<String>,
+    /// Handle et champs modifiés.
+    pub updated: Vec<(String, Vec<&'static str>)>,
+    pub unchanged: Vec<String>,
+    /// Absents du CSV, désactivés par `prune`.
+    pub deactivated: Vec<String>,
+}
+
+impl PeopleDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.deactivated.is_empty()
+    }
+}
+
+/// Mutation manuelle tracée dans le journal du roster.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum AuditAction {
+    /// Le shift passe de `from` à `to`.
+    Swap { from: PersonId, to: PersonId },
+    /// `to` couvre le segment `segment` découpé dans le shift de `from`.
+    Cover {
+        segment: ShiftId,
+        from: Option<PersonId>,
+        to: PersonId,
+    },
+    /// Le segment `segment` est rendu à `to`, titulaire du segment précédent.
+    Uncover {
+        segment: ShiftId,
+        from: Option<PersonId>,
+        to: Option<PersonId>,
+    },
+    /// `to` remplace `from` sur [start, end) sans découper le shift.
+    Override {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        from: Option<PersonId>,
+        to: PersonId,
+    },
+    /// Le shift est réattribué d'office (ou libéré si `to` est absent).
+    Reassign {
+        from: Option<PersonId>,
+        to: Option<PersonId>,
+    },
+}
+
+/// Entrée du journal d'audit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub at: DateTime<Utc>,
+    pub shift: ShiftId,
+    #[serde(flatten)]
+    pub action: AuditAction,
+    /// Règles enfreintes, acceptées explicitement (`--force`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forced: Vec<String>,
+}
+
+/// Destinataire d'un niveau d'escalade.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EscalationTarget {
+    /// Personne d'astreinte sur un shift de l'équipe ayant ce rôle, remplacements
+    /// ponctuels compris. `Primary` couvre aussi les shifts sans rôle.
+    OnCall(Role),
+    /// Personne fixe (responsable d'équipe...).
+    Person(PersonId),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscalationLevel {
+    pub target: EscalationTarget,
+    /// Minutes sans acquittement avant de passer au niveau suivant.
+    pub escalate_after_minutes: u32,
+}
+
+/// Chaîne d'escalade ordonnée d'une équipe : primaire, secondaire, responsable...
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscalationPolicy {
+    pub levels: Vec<EscalationLevel>,
+}
+
+/// Équipe et sa rotation : membres (dans l'ordre de rotation), shifts
+/// marqués à son nom et options d'assignation propres.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Team {
+    pub name: String,
+    pub members: Vec<PersonId>,
+    #[serde(default)]
+    pub options: AssignOptions,
+    /// Chaîne d'escalade des alertes de l'équipe.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalation: Option<EscalationPolicy>,
+}
+
+/// Plage de jour d'une région dans une rotation « follow-the-sun » :
+/// l'équipe `team` couvre chaque jour [start, end) en heure locale de `zone`
+/// (fin le lendemain si `end <= start`).
+///
+/// Syntaxe texte : `team:zone@HH:MM-HH:MM`, par exemple `eu:Europe/Paris@08:00-16:00`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Region {
+    pub team: String,
+    pub zone: Zone,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl FromStr for Region {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid region (expected team:zone@HH:MM-HH:MM): {s}");
+        let (team, rest) = s.trim().split_once(':').ok_or_else(invalid)?;
+        let (zone, window) = rest.split_once('@').ok_or_else(invalid)?;
+        let (start, end) = window.split_once('-').ok_or_else(invalid)?;
+        let time = |t: &str| {
+            NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|_| format!("invalid time: {t}"))
+        };
+        let region = Region {
+            team: team.trim().to_string(),
+            zone: zone.parse()?,
+            start: time(start)?,
+            end: time(end)?,
+        };
+        if region.team.is_empty() || region.start == region.end {
+            return Err(invalid());
+        }
+        Ok(region)
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}@{}-{}",
+            self.team,
+            self.zone,
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+/// Rotation « follow-the-sun » : régions dans l'ordre des passations,
+/// dont les plages locales s'enchaînent pour couvrir 24 h.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FollowTheSun {
+    pub name: String,
+    pub regions: Vec<Region>,
+}
+
+/// Remplacement ponctuel d'une portion de shift (rendez-vous médical...),
+/// sans découper le shift : `person` est d'astreinte sur [start, end).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Override {
+    pub shift_id: ShiftId,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub person: PersonId,
+}
+
+/// Portion d'un shift tenue par une même personne, remplacements appliqués.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment<'a> {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub holder: Option<&'a PersonId>,
+}
+
+/// Identifiant fort pour SwapRequest
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SwapRequestId(String);
+
+impl SwapRequestId {
+    pub fn new<S: AsRef<str>>(s: S) -> Self {
+        Self(s.as_ref().to_owned())
+    }
+    pub fn random() -> Self {
+        Self(Uuid::new_v4().to_string())
+    }
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// État d'une proposition d'échange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapStatus {
+    #[default]
+    Pending,
+    Accepted,
+    Rejected,
+    Expired,
+}
+
+impl fmt::Display for SwapStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SwapStatus::Pending => "pending",
+            SwapStatus::Accepted => "accepted",
+            SwapStatus::Rejected => "rejected",
+            SwapStatus::Expired => "expired",
+        })
+    }
+}
+
+/// Proposition d'échange négociée : `from` cède `offered` à `to`, qui lui
+/// cède `requested` en retour (1 pour 1, 1 pour N, ou don si vide).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapRequest {
+    pub id: SwapRequestId,
+    pub from: PersonId,
+    pub to: PersonId,
+    pub offered: Vec<ShiftId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requested: Vec<ShiftId>,
+    pub created_at: DateTime<Utc>,
+    /// Au-delà, la proposition ne peut plus être acceptée.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub status: SwapStatus,
+}
+
+impl SwapRequest {
+    /// Vrai si la proposition est en attente et non échue à `now`.
+    pub fn is_open(&self, now: DateTime<Utc>) -> bool {
+        self.status == SwapStatus::Pending && self.expires_at.map_or(true, |exp| now < exp)
+    }
 }
 
 /// Roster complet
@@ -125,6 +529,25 @@ impl Shift {
 pub struct Roster {
     pub people: Vec<Person>,
     pub shifts: Vec<Shift>,
+    /// Journal des swaps/covers (ordre chronologique).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub audit: Vec<AuditEntry>,
+    /// Propositions d'échange, y compris closes (historique).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub swap_requests: Vec<SwapRequest>,
+    /// Équipes ; une personne peut appartenir à plusieurs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub teams: Vec<Team>,
+    /// Remplacements ponctuels, appliqués par-dessus les assignations.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<Override>,
+    /// Rotations « follow-the-sun » entre équipes régionales.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub follow_the_sun: Vec<FollowTheSun>,
+    /// Fuseau de référence pour les fériés, limites et disponibilités ;
+    /// conservé d'une commande à l'autre.
+    #[serde(default, skip_serializing_if = "Zone::is_utc")]
+    pub zone: Zone,
 }
 
 impl Roster {
@@ -137,7 +560,167 @@ impl Roster {
     pub fn find_person_mut_by_id(&mut self, id: &PersonId) -> Option<&mut Person> {
         self.people.iter_mut().find(|p| &p.id == id)
     }
+    pub fn find_shift(&self, id: &ShiftId) -> Option<&Shift> {
+        self.shifts.iter().find(|s| &s.id == id)
+    }
     pub fn find_shift_mut(&mut self, id: &ShiftId) -> Option<&mut Shift> {
         self.shifts.iter_mut().find(|s| &s.id == id)
     }
+    pub fn find_team(&self, name: &str) -> Option<&Team> {
+        self.teams.iter().find(|t| t.name == name)
+    }
+    pub fn find_team_mut(&mut self, name: &str) -> Option<&mut Team> {
+        self.teams.iter_mut().find(|t| t.name == name)
+    }
+    pub fn find_follow_the_sun(&self, name: &str) -> Option<&FollowTheSun> {
+        self.follow_the_sun.iter().find(|r| r.name == name)
+    }
+    pub fn find_swap_request(&self, id: &SwapRequestId) -> Option<&SwapRequest> {
+        self.swap_requests.iter().find(|r| &r.id == id)
+    }
+
+    /// Import idempotent : met à jour par `handle` (l'identifiant existant est
+    /// conservé, donc les assignations aussi) et ajoute les nouveaux. Avec
+    /// `prune`, les membres actifs absents de `imported` sont désactivés ;
+    /// un membre désactivé qui réapparaît est réactivé (pas un membre archivé).
+    pub fn upsert_people(&mut self, imported: Vec<Person>, prune: bool) -> PeopleDiff {
+        self.upsert_people_with(imported, prune, &[])
+    }
+
+    /// Comme [`Roster::upsert_people`] ; les membres dont le handle figure dans
+    /// `rejected` (lignes écartées à l'import) ne sont ni modifiés ni désactivés.
+    pub fn upsert_people_with(
+        &mut self,
+        imported: Vec<Person>,
+        prune: bool,
+        rejected: &[String],
+    ) -> PeopleDiff {
+        let mut diff = PeopleDiff::default();
+        let mut seen: Vec<String> = rejected.to_vec();
+        for incoming in imported {
+            seen.push(incoming.handle.clone());
+            let Some(existing) = self.people.iter_mut().find(|p| p.handle == incoming.handle)
+            else {
+                diff.added.push(incoming.handle.clone());
+                self.people.push(incoming);
+                continue;
+            };
+            let mut changed = Vec::new();
+            if existing.display_name != incoming.display_name {
+                existing.display_name = incoming.display_name;
+                changed.push("display_name");
+            }
+            if existing.on_vacation != incoming.on_vacation {
+                existing.on_vacation = incoming.on_vacation;
+                changed.push("on_vacation");
+            }
+            if existing.vacations != incoming.vacations {
+                existing.vacations = incoming.vacations;
+                changed.push("vacations");
+            }
+            if existing.availability != incoming.availability {
+                existing.availability = incoming.availability;
+                changed.push("availability");
+            }
+            if existing.status == PersonStatus::Inactive {
+                existing.status = PersonStatus::Active;
+                changed.push("status");
+            }
+            if changed.is_empty() {
+                diff.unchanged.push(existing.handle.clone());
+            } else {
+                diff.updated.push((existing.handle.clone(), changed));
+            }
+        }
+        if prune {
+            for p in self.people.iter_mut() {
+                if p.is_active() && !seen.contains(&p.handle) {
+                    p.status = PersonStatus::Inactive;
+                    diff.deactivated.push(p.handle.clone());
+                }
+            }
+        }
+        diff
+    }
+
+    /// Remplacements du shift, dans l'ordre d'ajout (le dernier l'emporte).
+    pub fn overrides_for<'a>(&'a self, shift: &'a ShiftId) -> impl Iterator<Item = &'a Override> {
+        self.overrides.iter().filter(move |o| &o.shift_id == shift)
+    }
+
+    /// Personne d'astreinte sur le shift à l'instant `at`, remplacements compris.
+    pub fn holder_at<'a>(&'a self, shift: &'a Shift, at: DateTime<Utc>) -> Option<&'a PersonId> {
+        if !shift.contains(at) {
+            return None;
+        }
+        self.overrides_for(&shift.id)
+            .filter(|o| o.start <= at && at < o.end)
+            .last()
+            .map(|o| &o.person)
+            .or(shift.assigned.as_ref())
+    }
+
+    /// Découpage effectif du shift selon ses remplacements (segments contigus,
+    /// fusionnés quand la même personne se suit).
+    pub fn segments<'a>(&'a self, shift: &'a Shift) -> Vec<Segment<'a>> {
+        let overrides: Vec<&Override> = self.overrides_for(&shift.id).collect();
+        let mut cuts = vec![shift.start, shift.end];
+        for o in &overrides {
+            cuts.push(o.start.clamp(shift.start, shift.end));
+            cuts.push(o.end.clamp(shift.start, shift.end));
+        }
+        cuts.sort();
+        cuts.dedup();
+        let mut out: Vec<Segment<'a>> = Vec::new();
+        for w in cuts.windows(2) {
+            let holder = overrides
+                .iter()
+                .rev()
+                .find(|o| o.start <= w[0] && w[1] <= o.end)
+                .map(|o| &o.person)
+                .or(shift.assigned.as_ref());
+            match out.last_mut() {
+                Some(last) if last.holder == holder => last.end = w[1],
+                _ => out.push(Segment {
+                    start: w[0],
+                    end: w[1],
+                    holder,
+                }),
+            }
+        }
+        out
+    }
+
+    /// Shifts tenus à l'instant `at`, filtrés éventuellement par rôle.
+    ///
+    /// Les segments issus d'un `cover_shift` sont des shifts à part entière :
+    /// au point de reprise, seul le segment de la personne qui couvre est actif.
+    /// Un shift non assigné compte s'il est remplacé à cet instant.
+    pub fn shifts_at(&self, at: DateTime<Utc>, role: Option<&Role>) -> Vec<&Shift> {
+        let mut out: Vec<&Shift> = self
+            .shifts
+            .iter()
+            .filter(|s| self.holder_at(s, at).is_some())
+            .filter(|s| role.map_or(true, |r| s.role.as_ref() == Some(r)))
+            .collect();
+        out.sort_by_key(|s| s.start);
+        out
+    }
+
+    /// Personnes d'astreinte à l'instant `at` (sans doublon, ordre des shifts).
+    pub fn on_call_at(&self, at: DateTime<Utc>, role: Option<&Role>) -> Vec<&Person> {
+        let mut out: Vec<&Person> = Vec::new();
+        for shift in self.shifts_at(at, role) {
+            let Some(person) = self
+                .holder_at(shift, at)
+                .and_then(|pid| self.find_person_by_id(pid))
+            else {
+                continue;
+            };
+            if !out.iter().any(|p| p.id == person.id) {
+                out.push(person);
+            }
+        }
+        out
+    }
 }
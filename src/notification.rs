@@ -49,22 +49,31 @@ pub fn prepare_reminder(
         .find_person_by_handle(handle)
         .with_context(|| format!("unknown person handle: {handle}"))?;
 
-    let mut upcoming: Vec<&Shift> = roster
+    // Portions effectivement tenues, remplacements ponctuels compris : le
+    // rappel porte sur la portion, sous l'id et le nom du shift.
+    let upcoming = roster
         .shifts
         .iter()
-        .filter(|shift| shift.assigned.as_ref() == Some(&person.id) && shift.start >= now)
-        .collect();
+        .flat_map(|shift| {
+            roster
+                .segments(shift)
+                .into_iter()
+                .filter(|seg| seg.holder == Some(&person.id) && seg.start >= now)
+                .map(move |seg| Shift {
+                    start: seg.start,
+                    end: seg.end,
+                    ..shift.clone()
+                })
+        })
+        .min_by_key(|shift| shift.start);
 
-    if upcoming.is_empty() {
+    let Some(shift) = upcoming else {
         bail!("no upcoming shift found for handle {handle}");
-    }
-
-    upcoming.sort_by_key(|shift| shift.start);
-    let shift = upcoming[0];
+    };
 
     let notice_at = shift.start - Duration::days(days_before);
 
-    let content = renderer.render(person, shift, notice_at);
+    let content = renderer.render(person, &shift, notice_at);
     Ok(Reminder {
         person_handle: person.handle.clone(),
         shift_id: shift.id.as_str().to_string(),
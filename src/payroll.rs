@@ -0,0 +1,175 @@
+use crate::holiday::HolidayCalendar;
+use crate::model::Roster;
+use crate::tz::Zone;
+use anyhow::Context;
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Grille de taux horaires (fichier JSON).
+///
+/// ```json
+/// { "weekday": 2.5, "weekend": 4.0, "holiday": 6.0, "intervention": 25.0 }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateTable {
+    /// Heure d'astreinte en semaine.
+    pub weekday: f64,
+    /// Heure d'astreinte le samedi/dimanche.
+    pub weekend: f64,
+    /// Heure d'astreinte un jour férié (prioritaire sur le week-end).
+    pub holiday: f64,
+    /// Heure d'intervention effective, payée en plus de l'astreinte.
+    #[serde(default)]
+    pub intervention: f64,
+}
+
+impl RateTable {
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let data = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+        let rates: RateTable =
+            serde_json::from_slice(&data).with_context(|| "parsing rate table")?;
+        Ok(rates)
+    }
+}
+
+/// Catégorie tarifaire d'une heure d'astreinte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateBucket {
+    Weekday,
+    Weekend,
+    Holiday,
+}
+
+impl RateBucket {
+    pub fn classify(date: NaiveDate, holidays: &HolidayCalendar) -> Self {
+        if holidays.contains(date) {
+            RateBucket::Holiday
+        } else if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+            RateBucket::Weekend
+        } else {
+            RateBucket::Weekday
+        }
+    }
+}
+
+/// Intervention effective déclarée pour une personne.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Intervention {
+    pub handle: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Paramètres du calcul de paie.
+#[derive(Debug, Clone, Default)]
+pub struct PayrollOptions {
+    /// Fuseau définissant jours, week-ends et mois.
+    pub zone: Zone,
+    pub holidays: HolidayCalendar,
+    /// Restreint l'export à un mois (`YYYY-MM`).
+    pub month: Option<String>,
+}
+
+/// Ligne de paie mensuelle d'une personne.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PayrollLine {
+    pub month: String,
+    pub handle: String,
+    pub display_name: String,
+    pub weekday_hours: f64,
+    pub weekend_hours: f64,
+    pub holiday_hours: f64,
+    pub intervention_hours: f64,
+    pub amount: f64,
+}
+
+/// Ventile chaque shift assigné par mois et catégorie tarifaire.
+///
+/// Les segments créés par `cover_shift` sont des shifts distincts et les
+/// remplacements ponctuels découpent le shift : chaque personne est payée
+/// pour sa portion. Les shifts à cheval sur deux jours (ou
+/// deux mois) sont découpés aux minuits locaux.
+pub fn compute(
+    roster: &Roster,
+    rates: &RateTable,
+    opts: &PayrollOptions,
+    interventions: &[Intervention],
+) -> Vec<PayrollLine> {
+    let mut lines: BTreeMap<(String, String), PayrollLine> = BTreeMap::new();
+    let month_of = |date: NaiveDate| format!("{:04}-{:02}", date.year(), date.month());
+    let wanted = |month: &str| opts.month.as_deref().map_or(true, |m| m == month);
+
+    for segment in roster.shifts.iter().flat_map(|s| roster.segments(s)) {
+        let Some(person) = segment.holder.and_then(|pid| roster.find_person_by_id(pid)) else {
+            continue;
+        };
+        for (date, start, end) in opts.zone.split_days(segment.start, segment.end) {
+            let month = month_of(date);
+            if !wanted(&month) {
+                continue;
+            }
+            let hours = (end - start).num_minutes() as f64 / 60.0;
+            let line = lines
+                .entry((month.clone(), person.handle.clone()))
+                .or_insert_with(|| empty_line(month, &person.handle, &person.display_name));
+            match RateBucket::classify(date, &opts.holidays) {
+                RateBucket::Weekday => line.weekday_hours += hours,
+                RateBucket::Weekend => line.weekend_hours += hours,
+                RateBucket::Holiday => line.holiday_hours += hours,
+            }
+        }
+    }
+
+    for intervention in interventions {
+        let Some(person) = roster.find_person_by_handle(&intervention.handle) else {
+            continue;
+        };
+        let month = month_of(opts.zone.naive_local(intervention.start).date());
+        if !wanted(&month) {
+            continue;
+        }
+        let hours = (intervention.end - intervention.start).num_minutes() as f64 / 60.0;
+        lines
+            .entry((month.clone(), person.handle.clone()))
+            .or_insert_with(|| empty_line(month, &person.handle, &person.display_name))
+            .intervention_hours += hours;
+    }
+
+    lines
+        .into_values()
+        .map(|mut l| {
+            l.amount = round2(
+                l.weekday_hours * rates.weekday
+                    + l.weekend_hours * rates.weekend
+                    + l.holiday_hours * rates.holiday
+                    + l.intervention_hours * rates.intervention,
+            );
+            l.weekday_hours = round2(l.weekday_hours);
+            l.weekend_hours = round2(l.weekend_hours);
+            l.holiday_hours = round2(l.holiday_hours);
+            l.intervention_hours = round2(l.intervention_hours);
+            l
+        })
+        .collect()
+}
+
+fn empty_line(month: String, handle: &str, display_name: &str) -> PayrollLine {
+    PayrollLine {
+        month,
+        handle: handle.to_string(),
+        display_name: display_name.to_string(),
+        weekday_hours: 0.0,
+        weekend_hours: 0.0,
+        holiday_hours: 0.0,
+        intervention_hours: 0.0,
+        amount: 0.0,
+    }
+}
+
+fn round2(x: f64) -> f64 {
+    (x * 100.0).round() / 100.0
+}
@@ -0,0 +1,233 @@
+use crate::model::{Person, Role, Roster, Shift};
+use crate::tz::Zone;
+use chrono::{DateTime, Utc};
+use csv::WriterBuilder;
+use std::str::FromStr;
+
+/// Critères de sélection des shifts (tous optionnels, combinés en ET).
+#[derive(Debug, Clone, Default)]
+pub struct ShiftFilter {
+    /// Handle de la personne assignée.
+    pub handle: Option<String>,
+    /// Shifts se terminant après cet instant.
+    pub from: Option<DateTime<Utc>>,
+    /// Shifts commençant avant cet instant.
+    pub until: Option<DateTime<Utc>>,
+    /// Uniquement les shifts sans assignation.
+    pub unassigned: bool,
+    pub role: Option<Role>,
+    /// Sous-chaîne du nom (insensible à la casse).
+    pub name: Option<String>,
+    /// Équipe propriétaire.
+    pub team: Option<String>,
+}
+
+impl ShiftFilter {
+    pub fn matches(&self, roster: &Roster, shift: &Shift) -> bool {
+        if let Some(handle) = &self.handle {
+            let assigned = shift
+                .assigned
+                .as_ref()
+                .and_then(|pid| roster.find_person_by_id(pid));
+            if assigned.map(|p| p.handle.as_str()) != Some(handle.as_str()) {
+                return false;
+            }
+        }
+        if self.from.is_some_and(|from| shift.end <= from) {
+            return false;
+        }
+        if self.until.is_some_and(|until| shift.start >= until) {
+            return false;
+        }
+        if self.unassigned && shift.assigned.is_some() {
+            return false;
+        }
+        if self.role.is_some() && shift.role != self.role {
+            return false;
+        }
+        if let Some(name) = &self.name {
+            if !shift.name.to_lowercase().contains(&name.to_lowercase()) {
+                return false;
+            }
+        }
+        if self.team.is_some() && shift.team != self.team {
+            return false;
+        }
+        true
+    }
+}
+
+/// Clé de tri ; sans clé, l'ordre du fichier est conservé.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Start,
+    End,
+    Name,
+    Handle,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "start" => Ok(SortKey::Start),
+            "end" => Ok(SortKey::End),
+            "name" => Ok(SortKey::Name),
+            "handle" => Ok(SortKey::Handle),
+            _ => Err(format!("unknown sort key: {s}")),
+        }
+    }
+}
+
+/// Requête complète : filtre + tri.
+#[derive(Debug, Clone, Default)]
+pub struct ShiftQuery {
+    pub filter: ShiftFilter,
+    pub sort: Option<SortKey>,
+}
+
+/// Ligne de résultat : le shift et la personne assignée résolue.
+#[derive(Debug, Clone, Copy)]
+pub struct ShiftRow<'a> {
+    pub shift: &'a Shift,
+    pub person: Option<&'a Person>,
+}
+
+impl ShiftRow<'_> {
+    pub fn handle(&self) -> Option<&str> {
+        self.person.map(|p| p.handle.as_str())
+    }
+}
+
+impl ShiftQuery {
+    pub fn run<'a>(&self, roster: &'a Roster) -> Vec<ShiftRow<'a>> {
+        let mut rows: Vec<ShiftRow<'a>> = roster
+            .shifts
+            .iter()
+            .filter(|s| self.filter.matches(roster, s))
+            .map(|shift| ShiftRow {
+                shift,
+                person: shift
+                    .assigned
+                    .as_ref()
+                    .and_then(|pid| roster.find_person_by_id(pid)),
+            })
+            .collect();
+        match self.sort {
+            Some(SortKey::Start) => rows.sort_by_key(|r| r.shift.start),
+            Some(SortKey::End) => rows.sort_by_key(|r| r.shift.end),
+            Some(SortKey::Name) => rows.sort_by(|a, b| a.shift.name.cmp(&b.shift.name)),
+            Some(SortKey::Handle) => rows.sort_by(|a, b| a.handle().cmp(&b.handle())),
+            None => {}
+        }
+        rows
+    }
+}
+
+/// Format de sortie de `render`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// `id | start → end | handle`
+    #[default]
+    Table,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "table" | "text" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            _ => Err(format!("unknown output format: {s}")),
+        }
+    }
+}
+
+/// Rend les lignes dans le format demandé, heures exprimées dans `zone`.
+pub fn render(rows: &[ShiftRow<'_>], format: OutputFormat, zone: Zone) -> anyhow::Result<String> {
+    let role = |r: &ShiftRow<'_>| r.shift.role.as_ref().map(|x| x.to_string());
+    let out = match format {
+        OutputFormat::Table => {
+            let mut out = String::new();
+            for r in rows {
+                out.push_str(&format!(
+                    "{} | {} → {} | {}\n",
+                    r.shift.id.as_str(),
+                    zone.to_rfc3339(r.shift.start),
+                    zone.to_rfc3339(r.shift.end),
+                    r.handle().unwrap_or("-")
+                ));
+            }
+            out
+        }
+        OutputFormat::Json => {
+            let items: Vec<serde_json::Value> = rows
+                .iter()
+                .map(|r| {
+                    serde_json::json!({
+                        "id": r.shift.id.as_str(),
+                        "name": r.shift.name,
+                        "start": zone.to_rfc3339(r.shift.start),
+                        "end": zone.to_rfc3339(r.shift.end),
+                        "role": role(r),
+                        "assigned_handle": r.handle(),
+                        "assigned_name": r.person.map(|p| p.display_name.as_str()),
+                        "parent_id": r.shift.parent.as_ref().map(|p| p.as_str()),
+                    })
+                })
+                .collect();
+            let mut s = serde_json::to_string_pretty(&items)?;
+            s.push('\n');
+            s
+        }
+        OutputFormat::Csv => {
+            let mut w = WriterBuilder::new()
+                .has_headers(true)
+                .from_writer(Vec::new());
+            w.write_record(["id", "name", "start", "end", "role", "assigned_handle"])?;
+            for r in rows {
+                w.write_record([
+                    r.shift.id.as_str(),
+                    r.shift.name.as_str(),
+                    zone.to_rfc3339(r.shift.start).as_str(),
+                    zone.to_rfc3339(r.shift.end).as_str(),
+                    role(r).unwrap_or_default().as_str(),
+                    r.handle().unwrap_or(""),
+                ])?;
+            }
+            let bytes = w.into_inner().map_err(|e| anyhow::anyhow!("{e}"))?;
+            String::from_utf8(bytes)?
+        }
+        OutputFormat::Markdown => {
+            let mut out = String::from(
+                "| id | name | start | end | role | assigned |\n|---|---|---|---|---|---|\n",
+            );
+            for r in rows {
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} |\n",
+                    r.shift.id.as_str(),
+                    md_escape(&r.shift.name),
+                    zone.format(r.shift.start, "%Y-%m-%d %H:%M"),
+                    zone.format(r.shift.end, "%Y-%m-%d %H:%M"),
+                    md_escape(&role(r).unwrap_or_default()),
+                    r.person
+                        .map(|p| md_escape(&p.display_name))
+                        .unwrap_or_default()
+                ));
+            }
+            out
+        }
+    };
+    Ok(out)
+}
+
+fn md_escape(s: &str) -> String {
+    s.replace('|', "\\|")
+}
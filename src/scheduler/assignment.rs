@@ -1,6 +1,7 @@
-use super::{types::SchedError, util, AssignOptions, Scheduler};
+use super::{limits, types::SchedError, util, AssignOptions, AssignScope, Scheduler};
+use crate::model::ShiftId;
 use crate::model::{Person, PersonId, Shift};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 
 pub(super) fn assign_rotative(
     scheduler: &mut Scheduler,
@@ -12,29 +13,113 @@ pub(super) fn assign_rotative(
     }
 
     scheduler.roster.shifts.sort_by_key(|s| s.start);
+    // Les shifts d'équipe relèvent de `assign_team`.
+    let indices: Vec<usize> = (0..scheduler.roster.shifts.len())
+        .filter(|&idx| {
+            let s = &scheduler.roster.shifts[idx];
+            !s.locked && s.team.is_none()
+        })
+        .collect();
+    fill_shifts(scheduler, people, opts, &indices, 0)
+}
+
+/// Réassigne les shifts du périmètre appartenant à `team` (`None` = rotation
+/// générale) ; la rotation reprend après le dernier shift conservé de la même équipe.
+pub(super) fn assign_incremental(
+    scheduler: &mut Scheduler,
+    people: &[Person],
+    opts: AssignOptions,
+    scope: AssignScope,
+    team: Option<&str>,
+) -> Result<Vec<ShiftId>, SchedError> {
+    let mut indices: Vec<usize> = scheduler
+        .roster
+        .shifts
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| scope.contains(s) && s.team.as_deref() == team)
+        .map(|(idx, _)| idx)
+        .collect();
+    indices.sort_by_key(|&idx| scheduler.roster.shifts[idx].start);
+    let Some(&first) = indices.first() else {
+        return Ok(Vec::new());
+    };
+    if people.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // La rotation reprend après la personne du dernier shift conservé.
+    let first_start = scheduler.roster.shifts[first].start;
+    let cursor = scheduler
+        .roster
+        .shifts
+        .iter()
+        .enumerate()
+        .filter(|(idx, s)| {
+            !indices.contains(idx) && s.start < first_start && s.team.as_deref() == team
+        })
+        .filter_map(|(_, s)| Some((s.start, s.assigned.as_ref()?)))
+        .max_by_key(|(start, _)| *start)
+        .and_then(|(_, pid)| people.iter().position(|p| &p.id == pid))
+        .map_or(0, |pos| (pos + 1) % people.len());
+
+    for &idx in &indices {
+        scheduler.roster.shifts[idx].assigned = None;
+    }
+    fill_shifts(scheduler, people, opts, &indices, cursor)?;
+    Ok(indices
+        .into_iter()
+        .map(|idx| scheduler.roster.shifts[idx].id.clone())
+        .collect())
+}
+
+/// Assigne en round-robin les shifts d'indices `indices`, dans cet ordre,
+/// en commençant la rotation à `people[cursor]`.
+pub(super) fn fill_shifts(
+    scheduler: &mut Scheduler,
+    people: &[Person],
+    opts: AssignOptions,
+    indices: &[usize],
+    mut cursor: usize,
+) -> Result<(), SchedError> {
+    if people.is_empty() {
+        return Ok(());
+    }
+
     let total = people.len();
-    let mut cursor = 0usize;
 
-    for shift_index in 0..scheduler.roster.shifts.len() {
+    for &shift_index in indices {
         let candidate = scheduler.roster.shifts[shift_index].clone();
+        let balance = opts.balance_holidays && !scheduler.holiday_dates(&candidate).is_empty();
 
-        let chosen = (0..total).find_map(|_| {
-            let person = &people[cursor];
-            cursor = (cursor + 1) % total;
-
-            if person.on_vacation {
-                return None;
+        // Candidats valides dans l'ordre de rotation. Sur un férié avec
+        // équilibrage, le moins de fériés d'abord ; puis le meilleur score de
+        // préférences ; à égalité, l'ordre de rotation.
+        let mut best: Option<(usize, usize, i64)> = None;
+        for offset in 0..total {
+            let person = &people[(cursor + offset) % total];
+            if person.on_vacation || !person.is_active() {
+                continue;
             }
-
-            if scheduler.person_ok_for_shift(&person.id, &candidate, opts, Some(shift_index)) {
-                return Some(person.id.clone());
+            if !scheduler.person_ok_for_shift(&person.id, &candidate, opts, Some(shift_index)) {
+                continue;
             }
+            let count = if balance {
+                scheduler.holiday_shift_count(&person.id)
+            } else {
+                0
+            };
+            let score = scheduler.preference_score(&person.id, &candidate);
+            let better = best.map_or(true, |(_, c, sc)| count < c || (count == c && score > sc));
+            if better {
+                best = Some((offset, count, score));
+            }
+        }
 
-            None
-        });
-
-        if let Some(person_id) = chosen {
-            scheduler.roster.shifts[shift_index].assigned = Some(person_id);
+        if let Some((offset, _, _)) = best {
+            let idx = (cursor + offset) % total;
+            cursor = (idx + 1) % total;
+            scheduler.roster.shifts[shift_index].assigned = Some(people[idx].id.clone());
         }
     }
 
@@ -49,24 +134,16 @@ impl Scheduler {
         opts: AssignOptions,
         exclude_shift_index: Option<usize>,
     ) -> bool {
+        let profile = self.roster.find_person_by_id(person);
+        let opts = profile.map_or(opts, |p| opts.for_person(&p.limits));
         let mut prev_end: Option<DateTime<Utc>> = None;
         let mut consec = 0u32;
 
-        let mut assigned: Vec<&Shift> = self
-            .roster
-            .shifts
-            .iter()
-            .enumerate()
-            .filter(|(idx, s)| {
-                let same_person = s.assigned.as_ref() == Some(person);
-                let excluded = exclude_shift_index.map(|i| i == *idx).unwrap_or(false);
-                same_person && !excluded
-            })
-            .map(|(_, s)| s)
-            .collect();
-        assigned.sort_by_key(|s| s.start);
+        // Portions tenues, remplacements ponctuels compris.
+        let held = util::held_by(&self.roster, person, exclude_shift_index);
+        let assigned: Vec<&Shift> = held.iter().collect();
 
-        for s in assigned {
+        for s in &assigned {
             if util::overlaps(s.start, s.end, shift.start, shift.end) {
                 return false;
             }
@@ -90,8 +167,19 @@ impl Scheduler {
             return false;
         }
 
-        if let Some(p) = self.roster.find_person_by_id(person) {
-            if p.on_vacation {
+        if opts.holiday_rotation && self.same_holiday_last_year(shift, &assigned) {
+            return false;
+        }
+
+        if let Some(p) = profile {
+            if !p.limits.is_empty() {
+                let mut with = assigned.clone();
+                with.push(shift);
+                if limits::involves(&p.limits, &with, shift, self.roster.zone) {
+                    return false;
+                }
+            }
+            if p.on_vacation || !p.is_active() || !p.in_tenure(shift.start, shift.end) {
                 return false;
             }
             if p.vacations
@@ -100,8 +188,69 @@ impl Scheduler {
             {
                 return false;
             }
+            if !p
+                .availability
+                .iter()
+                .all(|r| r.allows(shift, self.roster.zone))
+            {
+                return false;
+            }
         }
 
         true
     }
 }
+
+impl Scheduler {
+    /// Dates fériées (locales) touchées par le shift.
+    pub(super) fn holiday_dates(&self, shift: &Shift) -> Vec<NaiveDate> {
+        if self.holidays.is_empty() {
+            return Vec::new();
+        }
+        self.roster
+            .zone
+            .local_dates(shift.start, shift.end)
+            .into_iter()
+            .filter(|d| self.holidays.contains(*d))
+            .collect()
+    }
+
+    /// Somme des préférences (`prefer`/`avoid`) de la personne pour ce shift.
+    pub(super) fn preference_score(&self, person: &PersonId, shift: &Shift) -> i64 {
+        self.roster.find_person_by_id(person).map_or(0, |p| {
+            p.availability
+                .iter()
+                .map(|r| r.score(shift, self.roster.zone))
+                .sum()
+        })
+    }
+
+    fn holiday_shift_count(&self, person: &PersonId) -> usize {
+        self.roster
+            .shifts
+            .iter()
+            .filter(|s| s.assigned.as_ref() == Some(person))
+            .filter(|s| !self.holiday_dates(s).is_empty())
+            .count()
+    }
+
+    /// Vrai si l'un des fériés du shift a déjà été tenu l'année précédente
+    /// dans `assigned` (shifts de la même personne).
+    fn same_holiday_last_year(&self, shift: &Shift, assigned: &[&Shift]) -> bool {
+        let wanted: Vec<(i32, String)> = self
+            .holiday_dates(shift)
+            .into_iter()
+            .filter_map(|d| Some((d.year() - 1, self.holidays.key(d)?)))
+            .collect();
+        if wanted.is_empty() {
+            return false;
+        }
+        assigned.iter().any(|s| {
+            self.holiday_dates(s).into_iter().any(|d| {
+                self.holidays
+                    .key(d)
+                    .is_some_and(|k| wanted.contains(&(d.year(), k)))
+            })
+        })
+    }
+}
@@ -1,20 +1,18 @@
-use super::{util, AssignOptions, Conflict, ConflictKind, Scheduler};
+use super::{limits, util, AssignOptions, Conflict, ConflictKind, Scheduler};
 use crate::model::Shift;
 
 pub(super) fn detect_conflicts(scheduler: &Scheduler, opts: AssignOptions) -> Vec<Conflict> {
     let mut out = Vec::new();
 
     for person in scheduler.roster.people.iter() {
-        let mut shifts: Vec<&Shift> = scheduler
-            .roster
-            .shifts
-            .iter()
-            .filter(|s| s.assigned.as_ref() == Some(&person.id))
-            .collect();
-        shifts.sort_by_key(|s| s.start);
+        let opts = opts.for_person(&person.limits);
+        // Portions tenues, remplacements ponctuels compris.
+        let held = util::held_by(&scheduler.roster, &person.id, None);
+        let shifts: Vec<&Shift> = held.iter().collect();
 
         for (idx, a) in shifts.iter().enumerate() {
-            for b in shifts.iter().skip(idx + 1) {
+            // deux portions d'un même shift autour d'un remplacement
+            for b in shifts.iter().skip(idx + 1).filter(|b| b.id != a.id) {
                 if util::overlaps(a.start, a.end, b.start, b.end) {
                     out.push(Conflict {
                         person: person.id.clone(),
@@ -35,6 +33,17 @@ pub(super) fn detect_conflicts(scheduler: &Scheduler, opts: AssignOptions) -> Ve
                 }
             }
         }
+
+        for (kind, shift_a, shift_b) in
+            limits::violations(&person.limits, &shifts, scheduler.roster.zone)
+        {
+            out.push(Conflict {
+                person: person.id.clone(),
+                shift_a,
+                shift_b,
+                kind,
+            });
+        }
     }
 
     out
@@ -0,0 +1,124 @@
+use super::{AssignScope, CoverageIssue, CoverageKind, SchedError, Scheduler};
+use crate::model::{FollowTheSun, Shift, ShiftId};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+fn rotation(scheduler: &Scheduler, name: &str) -> Result<FollowTheSun, SchedError> {
+    let rotation = scheduler
+        .roster
+        .find_follow_the_sun(name)
+        .cloned()
+        .ok_or_else(|| SchedError::UnknownRotation(name.to_string()))?;
+    for region in &rotation.regions {
+        if scheduler.roster.find_team(&region.team).is_none() {
+            return Err(SchedError::UnknownTeam(region.team.clone()));
+        }
+    }
+    Ok(rotation)
+}
+
+/// Un shift par région et par jour local, marqué à l'équipe de la région et
+/// au nom de la rotation.
+pub(super) fn generate(
+    scheduler: &mut Scheduler,
+    name: &str,
+    from: NaiveDate,
+    days: u32,
+) -> Result<Vec<ShiftId>, SchedError> {
+    let rotation = rotation(scheduler, name)?;
+    let mut created = Vec::new();
+    for day in from.iter_days().take(days as usize) {
+        for region in &rotation.regions {
+            let end_day = if region.end <= region.start {
+                day + Duration::days(1)
+            } else {
+                day
+            };
+            // Heure murale inexistante (passage à l'heure d'été) : pas de shift,
+            // le trou sera signalé par le contrôle de couverture.
+            let (Some(start), Some(end)) = (
+                region.zone.from_local(day.and_time(region.start)),
+                region.zone.from_local(end_day.and_time(region.end)),
+            ) else {
+                continue;
+            };
+            let label = format!("{} {} {}", rotation.name, region.team, day);
+            let id = scheduler.create_shift(&label, start, end)?;
+            if let Some(shift) = scheduler.roster.find_shift_mut(&id) {
+                shift.team = Some(region.team.clone());
+                shift.rotation = Some(rotation.name.clone());
+            }
+            created.push(id);
+        }
+    }
+    Ok(created)
+}
+
+/// Assigne les shifts de chaque région parmi les membres de son équipe.
+pub(super) fn assign(
+    scheduler: &mut Scheduler,
+    name: &str,
+    scope: AssignScope,
+) -> Result<Vec<ShiftId>, SchedError> {
+    let rotation = rotation(scheduler, name)?;
+    let mut touched = Vec::new();
+    let mut done: Vec<&str> = Vec::new();
+    for region in &rotation.regions {
+        if done.contains(&region.team.as_str()) {
+            continue;
+        }
+        done.push(&region.team);
+        touched.extend(scheduler.assign_team(&region.team, scope)?);
+    }
+    Ok(touched)
+}
+
+/// Trous et chevauchements entre les shifts générés pour la rotation sur
+/// [from, until) ; les autres shifts des équipes régionales sont ignorés.
+pub(super) fn coverage(
+    scheduler: &Scheduler,
+    name: &str,
+    from: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<Vec<CoverageIssue>, SchedError> {
+    if until <= from {
+        return Err(SchedError::InvalidTimeRange);
+    }
+    let rotation = rotation(scheduler, name)?;
+    let mut shifts: Vec<&Shift> = scheduler
+        .roster
+        .shifts
+        .iter()
+        .filter(|s| s.rotation.as_deref() == Some(rotation.name.as_str()))
+        .filter(|s| s.start < until && from < s.end)
+        .collect();
+    shifts.sort_by_key(|s| s.start);
+
+    let mut issues = Vec::new();
+    let mut covered = from;
+    for shift in shifts {
+        let start = shift.start.max(from);
+        let end = shift.end.min(until);
+        if start > covered {
+            issues.push(CoverageIssue {
+                kind: CoverageKind::Gap,
+                start: covered,
+                end: start,
+            });
+        } else if start < covered {
+            issues.push(CoverageIssue {
+                kind: CoverageKind::Overlap,
+                start,
+                end: end.min(covered),
+            });
+        }
+        covered = covered.max(end);
+    }
+    if covered < until {
+        issues.push(CoverageIssue {
+            kind: CoverageKind::Gap,
+            start: covered,
+            end: until,
+        });
+    }
+    Ok(issues)
+}
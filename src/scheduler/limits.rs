@@ -0,0 +1,143 @@
+use super::ConflictKind;
+use crate::model::{PersonLimits, Shift, ShiftId};
+use crate::tz::Zone;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::collections::BTreeMap;
+
+/// Dépassement d'une limite individuelle : premier shift du groupe et shift fautif.
+pub(super) type LimitViolation = (ConflictKind, ShiftId, ShiftId);
+
+/// Dépassements des limites individuelles sur les shifts d'une personne.
+pub(super) fn violations(
+    limits: &PersonLimits,
+    shifts: &[&Shift],
+    zone: Zone,
+) -> Vec<LimitViolation> {
+    breaches(limits, shifts, zone)
+        .into_iter()
+        .map(|(v, _)| v)
+        .collect()
+}
+
+/// Vrai si `candidate` (présent dans `shifts`) fait partie d'un dépassement,
+/// même si la personne dépassait déjà la limite sans lui.
+pub(super) fn involves(
+    limits: &PersonLimits,
+    shifts: &[&Shift],
+    candidate: &Shift,
+    zone: Zone,
+) -> bool {
+    breaches(limits, shifts, zone)
+        .iter()
+        .any(|(_, members)| members.iter().any(|s| s.id == candidate.id))
+}
+
+/// Dépassements avec l'ensemble des shifts qui y participent.
+fn breaches<'a>(
+    limits: &PersonLimits,
+    shifts: &[&'a Shift],
+    zone: Zone,
+) -> Vec<(LimitViolation, Vec<&'a Shift>)> {
+    let mut shifts: Vec<&Shift> = shifts.to_vec();
+    shifts.sort_by_key(|s| s.start);
+    let mut out = Vec::new();
+
+    if let Some(max) = limits.max_shifts_per_month {
+        let mut months: BTreeMap<(i32, u32), Vec<&Shift>> = BTreeMap::new();
+        for s in &shifts {
+            let d = zone.naive_local(s.start).date();
+            let month = months.entry((d.year(), d.month())).or_default();
+            // portions d'un même shift (remplacements) : un seul shift
+            if !month.iter().any(|m| m.id == s.id) {
+                month.push(s);
+            }
+        }
+        for group in months.values() {
+            if group.len() > max as usize {
+                let v = (
+                    ConflictKind::MonthlyShiftLimit,
+                    group[0].id.clone(),
+                    group[max as usize].id.clone(),
+                );
+                out.push((v, group.clone()));
+            }
+        }
+    }
+
+    if let Some(max) = limits.max_hours_per_7_days {
+        let cap = i64::from(max) * 60;
+        let week = Duration::days(7);
+        // Le maximum d'une fenêtre glissante est atteint quand elle commence au
+        // début d'un shift ou se termine à la fin d'un shift.
+        let windows = shifts
+            .iter()
+            .flat_map(|s| [(s.start, s.start + week), (s.end - week, s.end)]);
+        for (ws, we) in windows {
+            let inside: Vec<&Shift> = shifts
+                .iter()
+                .copied()
+                .filter(|s| s.start < we && ws < s.end)
+                .collect();
+            let minutes: i64 = inside
+                .iter()
+                .map(|s| (s.end.min(we) - s.start.max(ws)).num_minutes())
+                .sum();
+            if minutes > cap {
+                let v = (
+                    ConflictKind::WeeklyHoursLimit,
+                    inside[0].id.clone(),
+                    inside[inside.len() - 1].id.clone(),
+                );
+                match out.iter_mut().find(|(o, _)| o == &v) {
+                    Some((_, members)) => {
+                        for s in inside {
+                            if !members.iter().any(|m| m.id == s.id) {
+                                members.push(s);
+                            }
+                        }
+                    }
+                    None => out.push((v, inside)),
+                }
+            }
+        }
+    }
+
+    if let Some(max) = limits.max_weekends_per_quarter {
+        // week-end identifié par son samedi -> shifts qui le touchent, le
+        // premier le représentant
+        let mut weekends: BTreeMap<NaiveDate, Vec<&Shift>> = BTreeMap::new();
+        for s in &shifts {
+            for d in zone.local_dates(s.start, s.end) {
+                let saturday = match d.weekday() {
+                    Weekday::Sat => d,
+                    Weekday::Sun => d - Duration::days(1),
+                    _ => continue,
+                };
+                let touching = weekends.entry(saturday).or_default();
+                if !touching.iter().any(|t| t.id == s.id) {
+                    touching.push(s);
+                }
+            }
+        }
+        let mut quarters: BTreeMap<(i32, u32), Vec<Vec<&Shift>>> = BTreeMap::new();
+        for (saturday, touching) in weekends {
+            let q = (saturday.month() - 1) / 3;
+            quarters
+                .entry((saturday.year(), q))
+                .or_default()
+                .push(touching);
+        }
+        for group in quarters.values() {
+            if group.len() > max as usize {
+                let v = (
+                    ConflictKind::QuarterlyWeekendLimit,
+                    group[0][0].id.clone(),
+                    group[max as usize][0].id.clone(),
+                );
+                out.push((v, group.concat()));
+            }
+        }
+    }
+
+    out
+}
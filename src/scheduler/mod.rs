@@ -1,27 +1,60 @@
 mod assignment;
 mod conflicts;
+mod follow_the_sun;
+mod limits;
 mod mutate;
+mod trade;
+mod transaction;
 mod types;
 mod util;
 
-pub use types::{AssignOptions, Conflict, ConflictKind, SchedError};
+pub use crate::model::AssignOptions;
+pub use transaction::Transaction;
+pub use types::{
+    AssignScope, Conflict, ConflictKind, CoverageIssue, CoverageKind, OffboardReport, SchedError,
+};
 
-use crate::model::{Person, PersonId, Roster, Shift, ShiftId};
-use chrono::{DateTime, Utc};
+use crate::holiday::HolidayCalendar;
+use crate::model::{Override, Person, PersonId, Roster, Shift, ShiftId, SwapRequestId};
+use crate::tz::Zone;
+use chrono::{DateTime, NaiveDate, Utc};
 
 /// Scheduler : encapsule un Roster en cours de construction
 #[derive(Debug, Default)]
 pub struct Scheduler {
     roster: Roster,
+    holidays: HolidayCalendar,
 }
 
 impl Scheduler {
     pub fn new() -> Self {
         Self {
             roster: Roster::default(),
+            holidays: HolidayCalendar::default(),
         }
     }
 
+    /// Calendrier de fériés utilisé par l'équilibrage et la rotation des fériés ;
+    /// les dates sont évaluées dans `zone`.
+    pub fn set_holidays(&mut self, holidays: HolidayCalendar, zone: Zone) {
+        self.holidays = holidays;
+        self.set_zone(zone);
+    }
+
+    /// Fuseau de référence pour les fériés et les disponibilités récurrentes,
+    /// enregistré dans le roster.
+    pub fn set_zone(&mut self, zone: Zone) {
+        self.roster.zone = zone;
+    }
+
+    pub fn zone(&self) -> Zone {
+        self.roster.zone
+    }
+
+    pub fn holidays(&self) -> &HolidayCalendar {
+        &self.holidays
+    }
+
     pub fn roster(&self) -> &Roster {
         &self.roster
     }
@@ -50,6 +83,7 @@ impl Scheduler {
         Ok(id)
     }
 
+    /// Assigne en round-robin tous les shifts non verrouillés hors équipe.
     pub fn assign_rotative(
         &mut self,
         people: &[Person],
@@ -58,10 +92,113 @@ impl Scheduler {
         assignment::assign_rotative(self, people, opts)
     }
 
+    /// Réassigne uniquement les shifts du périmètre `scope`, sans réordonner
+    /// le roster ni toucher aux autres assignations. Renvoie les shifts traités.
+    /// Les shifts d'équipe relèvent de [`Scheduler::assign_team`].
+    pub fn assign_incremental(
+        &mut self,
+        people: &[Person],
+        opts: AssignOptions,
+        scope: AssignScope,
+    ) -> Result<Vec<ShiftId>, SchedError> {
+        assignment::assign_incremental(self, people, opts, scope, None)
+    }
+
+    /// Assigne les shifts de l'équipe `team` parmi ses membres actifs, avec ses
+    /// propres options. Les shifts des autres équipes comptent comme contraintes :
+    /// personne n'est d'astreinte pour deux équipes à la fois.
+    pub fn assign_team(
+        &mut self,
+        team: &str,
+        scope: AssignScope,
+    ) -> Result<Vec<ShiftId>, SchedError> {
+        let rotation = self
+            .roster
+            .find_team(team)
+            .ok_or_else(|| SchedError::UnknownTeam(team.to_string()))?;
+        let opts = rotation.options;
+        let people: Vec<Person> = rotation
+            .members
+            .iter()
+            .filter_map(|pid| self.roster.find_person_by_id(pid))
+            .filter(|p| p.is_active() && !p.on_vacation)
+            .cloned()
+            .collect();
+        assignment::assign_incremental(self, &people, opts, scope, Some(team))
+    }
+
+    /// Génère `days` jours de la rotation « follow-the-sun » `name` à partir de
+    /// `from` : pour chaque jour, un shift par région sur sa plage locale.
+    pub fn generate_follow_the_sun(
+        &mut self,
+        name: &str,
+        from: NaiveDate,
+        days: u32,
+    ) -> Result<Vec<ShiftId>, SchedError> {
+        follow_the_sun::generate(self, name, from, days)
+    }
+
+    /// Assigne les shifts de chaque région parmi les membres de son équipe
+    /// (voir [`Scheduler::assign_team`]).
+    pub fn assign_follow_the_sun(
+        &mut self,
+        name: &str,
+        scope: AssignScope,
+    ) -> Result<Vec<ShiftId>, SchedError> {
+        follow_the_sun::assign(self, name, scope)
+    }
+
+    /// Vérifie que les passations entre régions s'enchaînent sur [from, until) :
+    /// renvoie les trous et chevauchements, vide si la couverture est continue.
+    pub fn follow_the_sun_coverage(
+        &self,
+        name: &str,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<CoverageIssue>, SchedError> {
+        follow_the_sun::coverage(self, name, from, until)
+    }
+
+    /// Retire `person` de la rotation à partir de `from` : statut archivé,
+    /// shifts futurs désassignés puis réassignés parmi `people`, remplacements
+    /// futurs supprimés (historique conservé).
+    pub fn offboard(
+        &mut self,
+        person: &PersonId,
+        from: DateTime<Utc>,
+        people: &[Person],
+        opts: AssignOptions,
+    ) -> Result<OffboardReport, SchedError> {
+        mutate::offboard(self, person, from, people, opts)
+    }
+
     pub fn detect_conflicts(&self, opts: AssignOptions) -> Vec<Conflict> {
         conflicts::detect_conflicts(self, opts)
     }
 
+    /// Shifts déjà assignés à `person` et bloqués par ses congés
+    /// (marge de repos incluse).
+    pub fn vacation_conflicts(&self, person: &PersonId, opts: AssignOptions) -> Vec<ShiftId> {
+        let Some(p) = self.roster.find_person_by_id(person) else {
+            return Vec::new();
+        };
+        let opts = opts.for_person(&p.limits);
+        self.roster
+            .shifts
+            .iter()
+            .filter(|s| s.assigned.as_ref() == Some(person))
+            .filter(|s| {
+                p.on_vacation
+                    || p.vacations
+                        .iter()
+                        .any(|vac| util::vacation_blocks_shift(vac, s, opts))
+            })
+            .map(|s| s.id.clone())
+            .collect()
+    }
+
+    /// Passe le shift de `a` à `b` (ou l'inverse) si la personne qui le reçoit
+    /// respecte toutes les règles d'assignation (congés, repos, limites...).
     pub fn swap(
         &mut self,
         shift_id: &ShiftId,
@@ -69,7 +206,19 @@ impl Scheduler {
         b: &PersonId,
         opts: AssignOptions,
     ) -> Result<(), SchedError> {
-        mutate::swap(self, shift_id, a, b, opts)
+        mutate::swap(self, shift_id, a, b, opts, false)
+    }
+
+    /// Comme [`Scheduler::swap`], même sur un shift verrouillé ou en enfreignant
+    /// des règles ; celles-ci sont listées dans l'entrée d'audit (`forced`).
+    pub fn swap_forced(
+        &mut self,
+        shift_id: &ShiftId,
+        a: &PersonId,
+        b: &PersonId,
+        opts: AssignOptions,
+    ) -> Result<(), SchedError> {
+        mutate::swap(self, shift_id, a, b, opts, true)
     }
 
     pub fn cover_shift(
@@ -79,6 +228,130 @@ impl Scheduler {
         person: &PersonId,
         opts: AssignOptions,
     ) -> Result<ShiftId, SchedError> {
-        mutate::cover_shift(self, shift_id, from, person, opts)
+        mutate::cover_shift(self, shift_id, from, person, opts, false)
+    }
+
+    /// Comme [`Scheduler::cover_shift`], y compris sur un shift verrouillé.
+    /// Le segment créé hérite du verrou.
+    pub fn cover_shift_forced(
+        &mut self,
+        shift_id: &ShiftId,
+        from: DateTime<Utc>,
+        person: &PersonId,
+        opts: AssignOptions,
+    ) -> Result<ShiftId, SchedError> {
+        mutate::cover_shift(self, shift_id, from, person, opts, true)
+    }
+
+    /// Verrouille (ou déverrouille) un shift, en l'assignant éventuellement à `person`.
+    pub fn set_locked(
+        &mut self,
+        shift_id: &ShiftId,
+        locked: bool,
+        person: Option<&PersonId>,
+    ) -> Result<(), SchedError> {
+        if let Some(pid) = person {
+            if self.roster.find_person_by_id(pid).is_none() {
+                return Err(SchedError::UnknownPerson(pid.as_str().to_string()));
+            }
+        }
+        let shift = self
+            .roster
+            .find_shift_mut(shift_id)
+            .ok_or_else(|| SchedError::UnknownShift(shift_id.as_str().to_string()))?;
+        if let Some(pid) = person {
+            shift.assigned = Some(pid.clone());
+        }
+        shift.locked = locked;
+        Ok(())
+    }
+
+    /// Annule un cover : le segment `segment` est rattaché au segment qui le
+    /// précède, dont le titulaire reprend la fin du shift. Renvoie le shift conservé.
+    pub fn uncover(
+        &mut self,
+        segment: &ShiftId,
+        opts: AssignOptions,
+    ) -> Result<ShiftId, SchedError> {
+        mutate::uncover(self, segment, opts)
+    }
+
+    /// Recolle les segments contigus du shift logique de `shift_id` tenus par
+    /// la même personne (après un cover puis un swap retour, par exemple).
+    pub fn merge_shifts(&mut self, shift_id: &ShiftId) -> Result<usize, SchedError> {
+        mutate::merge_shifts(self, shift_id)
+    }
+
+    /// Fait tenir [start, end) du shift par `person` sans le découper
+    /// (remplacement ponctuel, mêmes contrôles qu'un cover).
+    pub fn add_override(
+        &mut self,
+        shift_id: &ShiftId,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        person: &PersonId,
+        opts: AssignOptions,
+    ) -> Result<(), SchedError> {
+        mutate::add_override(self, shift_id, start, end, person, opts)
+    }
+
+    /// Retire le `index`-ième remplacement du shift (ordre d'ajout).
+    pub fn remove_override(
+        &mut self,
+        shift_id: &ShiftId,
+        index: usize,
+    ) -> Result<Override, SchedError> {
+        let pos = self
+            .roster
+            .overrides
+            .iter()
+            .enumerate()
+            .filter(|(_, o)| &o.shift_id == shift_id)
+            .nth(index)
+            .map(|(pos, _)| pos)
+            .ok_or_else(|| SchedError::UnknownOverride(shift_id.as_str().to_string(), index))?;
+        Ok(self.roster.overrides.remove(pos))
+    }
+
+    /// Applique plusieurs mutations (swap, cover, réassignation, création,
+    /// suppression) puis valide l'état final une seule fois : tout est
+    /// conservé, ou tout est annulé si `f` ou la validation échoue.
+    pub fn transaction<T, F>(&mut self, opts: AssignOptions, f: F) -> Result<T, SchedError>
+    where
+        F: FnOnce(&mut Transaction<'_>) -> Result<T, SchedError>,
+    {
+        transaction::run(self, opts, f)
+    }
+
+    /// Enregistre une proposition d'échange : `from` cède `offered` à `to`
+    /// contre `requested` (éventuellement vide).
+    pub fn propose_swap(
+        &mut self,
+        from: &PersonId,
+        to: &PersonId,
+        offered: Vec<ShiftId>,
+        requested: Vec<ShiftId>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<SwapRequestId, SchedError> {
+        trade::propose(self, from, to, offered, requested, expires_at)
+    }
+
+    /// Accepte une proposition : tous les échanges sont appliqués dans une
+    /// transaction et validés ensemble sur l'état final.
+    pub fn accept_swap_request(
+        &mut self,
+        id: &SwapRequestId,
+        opts: AssignOptions,
+    ) -> Result<(), SchedError> {
+        trade::accept(self, id, opts)
+    }
+
+    pub fn reject_swap_request(&mut self, id: &SwapRequestId) -> Result<(), SchedError> {
+        trade::reject(self, id)
+    }
+
+    /// Marque comme expirées les propositions en attente échues à `now`.
+    pub fn expire_swap_requests(&mut self, now: DateTime<Utc>) -> Vec<SwapRequestId> {
+        trade::expire(self, now)
     }
 }
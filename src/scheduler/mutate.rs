@@ -1,56 +1,119 @@
-use super::{util, AssignOptions, ConflictKind, SchedError, Scheduler};
-use crate::model::{PersonId, Shift, ShiftId};
+use super::{assignment, util, AssignOptions, Conflict, OffboardReport, SchedError, Scheduler};
+use crate::model::{
+    AuditAction, AuditEntry, Override, Person, PersonId, PersonStatus, Shift, ShiftId,
+};
 use chrono::{DateTime, Utc};
 
+/// Sans `force`, la première règle enfreinte est refusée ; avec `force`,
+/// le swap est appliqué et les règles enfreintes sont tracées dans l'audit.
 pub(super) fn swap(
     scheduler: &mut Scheduler,
     shift_id: &ShiftId,
     a: &PersonId,
     b: &PersonId,
     opts: AssignOptions,
+    force: bool,
 ) -> Result<(), SchedError> {
     let Some(pos) = util::find_shift_index(&scheduler.roster.shifts, shift_id) else {
         return Err(SchedError::UnknownShift(shift_id.as_str().to_string()));
     };
 
-    let (target, prev) = {
-        let shift = &scheduler.roster.shifts[pos];
-        let target = if shift.assigned.as_ref() == Some(a) {
-            b.clone()
-        } else if shift.assigned.as_ref() == Some(b) {
-            a.clone()
-        } else {
-            return Err(SchedError::SwapInvalid(
-                "shift not assigned to either person",
-            ));
-        };
-        (target, shift.assigned.clone())
-    };
+    let target = swap_target(&scheduler.roster.shifts[pos], a, b)?;
+    let violations = swap_violations(scheduler, pos, &target, opts);
+    if let (Some(first), false) = (violations.first(), force) {
+        return Err(SchedError::SwapInvalid(first));
+    }
+
+    let prev = scheduler.roster.shifts[pos]
+        .assigned
+        .replace(target.clone());
+    if let Some(from) = prev {
+        scheduler.roster.audit.push(AuditEntry {
+            at: Utc::now(),
+            shift: shift_id.clone(),
+            action: AuditAction::Swap { from, to: target },
+            forced: violations.iter().map(|v| v.to_string()).collect(),
+        });
+    }
+    Ok(())
+}
 
-    if let Some(person) = scheduler.roster.find_person_by_id(&target) {
+/// Règles enfreintes si `target` reprend le shift d'indice `pos`
+/// (mêmes règles que l'assignation automatique).
+fn swap_violations(
+    scheduler: &mut Scheduler,
+    pos: usize,
+    target: &PersonId,
+    opts: AssignOptions,
+) -> Vec<&'static str> {
+    let mut out = Vec::new();
+    let shift = scheduler.roster.shifts[pos].clone();
+    if shift.locked {
+        out.push("shift locked");
+    }
+    if let Some(person) = scheduler.roster.find_person_by_id(target) {
+        if !person.is_active() {
+            out.push("target person inactive");
+        }
         if person.on_vacation {
-            return Err(SchedError::SwapInvalid("target person on vacation"));
+            out.push("target person on vacation");
         }
         if person
             .vacations
             .iter()
-            .any(|vac| util::vacation_blocks_shift(vac, &scheduler.roster.shifts[pos], opts))
+            .any(|vac| util::vacation_blocks_shift(vac, &shift, opts))
         {
-            return Err(SchedError::SwapInvalid("target person on vacation range"));
+            out.push("target person on vacation range");
         }
     }
+    if scheduler.person_ok_for_shift(target, &shift, opts, Some(pos)) {
+        return out;
+    }
 
-    scheduler.roster.shifts[pos].assigned = Some(target.clone());
+    // Détail : conflits que le swap ferait apparaître pour `target`.
+    let key = |c: &Conflict| (c.kind.clone(), c.shift_a.clone(), c.shift_b.clone());
+    let of_target = |conflicts: Vec<Conflict>| -> Vec<_> {
+        conflicts
+            .iter()
+            .filter(|c| &c.person == target)
+            .map(key)
+            .collect()
+    };
+    let before = of_target(scheduler.detect_conflicts(opts));
+    let prev = scheduler.roster.shifts[pos]
+        .assigned
+        .replace(target.clone());
+    let after = of_target(scheduler.detect_conflicts(opts));
+    scheduler.roster.shifts[pos].assigned = prev;
 
-    let conflicts = scheduler.detect_conflicts(opts);
-    let severe = conflicts
-        .iter()
-        .any(|c| c.person == target && c.kind == ConflictKind::Overlap);
-    if severe {
-        scheduler.roster.shifts[pos].assigned = prev;
-        return Err(SchedError::SwapInvalid("introduces overlap"));
+    let len = out.len();
+    for (kind, _, _) in after.into_iter().filter(|c| !before.contains(c)) {
+        if !out.contains(&kind.as_str()) {
+            out.push(kind.as_str());
+        }
+    }
+    if out.len() == len {
+        // disponibilités, créneaux consécutifs, période d'activité, rotation des fériés
+        out.push("assignment constraints violated");
+    }
+    out
+}
+
+/// Personne qui reçoit le shift : l'autre des deux.
+pub(super) fn swap_target(
+    shift: &Shift,
+    a: &PersonId,
+    b: &PersonId,
+) -> Result<PersonId, SchedError> {
+    if shift.assigned.as_ref() == Some(a) {
+        Ok(b.clone())
+    } else if shift.assigned.as_ref() == Some(b) {
+        Ok(a.clone())
+    } else {
+        Err(SchedError::SwapInvalid(
+            "shift not assigned to either person",
+        ))
     }
-    Ok(())
 }
 
 pub(super) fn cover_shift(
@@ -59,53 +122,353 @@ pub(super) fn cover_shift(
     from: DateTime<Utc>,
     person: &PersonId,
     opts: AssignOptions,
+    force: bool,
 ) -> Result<ShiftId, SchedError> {
     let Some(pos) = util::find_shift_index(&scheduler.roster.shifts, shift_id) else {
         return Err(SchedError::UnknownShift(shift_id.as_str().to_string()));
     };
+    if scheduler.roster.shifts[pos].locked && !force {
+        return Err(SchedError::CoverInvalid("shift locked"));
+    }
 
     let cover = scheduler
         .roster
         .find_person_by_id(person)
         .ok_or_else(|| SchedError::UnknownPerson(person.as_str().to_string()))?;
+    if !cover.is_active() {
+        return Err(SchedError::CoverInvalid("person inactive"));
+    }
     if cover.on_vacation {
         return Err(SchedError::CoverInvalid("person on vacation"));
     }
 
     let original = scheduler.roster.shifts[pos].clone();
+    let new_segment = cover_segment(&original, from)?;
+
+    if cover
+        .vacations
+        .iter()
+        .any(|vac| util::vacation_blocks_shift(vac, &new_segment, opts))
+    {
+        return Err(SchedError::CoverInvalid("person vacation conflicts"));
+    }
+
+    if !scheduler.person_ok_for_shift(person, &new_segment, opts, None) {
+        return Err(SchedError::CoverInvalid("assignment constraints violated"));
+    }
+
+    let forced = if original.locked {
+        vec!["shift locked".to_string()]
+    } else {
+        Vec::new()
+    };
+    Ok(split_for_cover(scheduler, pos, new_segment, person, forced))
+}
+
+/// Rend un segment de cover au titulaire du segment qui le précède.
+pub(super) fn uncover(
+    scheduler: &mut Scheduler,
+    segment_id: &ShiftId,
+    opts: AssignOptions,
+) -> Result<ShiftId, SchedError> {
+    let Some(pos) = util::find_shift_index(&scheduler.roster.shifts, segment_id) else {
+        return Err(SchedError::UnknownShift(segment_id.as_str().to_string()));
+    };
+    let segment = scheduler.roster.shifts[pos].clone();
+    if segment.parent.is_none() {
+        return Err(SchedError::CoverInvalid("shift is not a cover segment"));
+    }
+    let prev_pos = scheduler
+        .roster
+        .shifts
+        .iter()
+        .position(|s| s.origin() == segment.origin() && s.end == segment.start)
+        .ok_or(SchedError::CoverInvalid("no adjacent segment before"))?;
+    let prev = &scheduler.roster.shifts[prev_pos];
+    if prev.locked || segment.locked {
+        return Err(SchedError::CoverInvalid("shift locked"));
+    }
+
+    let merged = Shift {
+        end: segment.end,
+        ..prev.clone()
+    };
+    if let Some(holder) = &merged.assigned {
+        if !scheduler.person_ok_for_shift(holder, &merged, opts, Some(prev_pos)) {
+            return Err(SchedError::CoverInvalid("assignment constraints violated"));
+        }
+    }
+
+    let kept = merged.id.clone();
+    let to = merged.assigned.clone();
+    scheduler.roster.shifts[prev_pos] = merged;
+    absorb(scheduler, pos, &kept);
+    scheduler.roster.audit.push(AuditEntry {
+        at: Utc::now(),
+        shift: kept.clone(),
+        action: AuditAction::Uncover {
+            segment: segment_id.clone(),
+            from: segment.assigned,
+            to,
+        },
+        forced: Vec::new(),
+    });
+    Ok(kept)
+}
+
+/// Recolle les segments contigus d'un même shift logique tenus par la même
+/// personne. Renvoie le nombre de segments absorbés.
+pub(super) fn merge_shifts(
+    scheduler: &mut Scheduler,
+    shift_id: &ShiftId,
+) -> Result<usize, SchedError> {
+    let origin = scheduler
+        .roster
+        .find_shift(shift_id)
+        .map(|s| s.origin().clone())
+        .ok_or_else(|| SchedError::UnknownShift(shift_id.as_str().to_string()))?;
+
+    let mut merged = 0;
+    loop {
+        let mut parts: Vec<&Shift> = scheduler
+            .roster
+            .shifts
+            .iter()
+            .filter(|s| s.origin() == &origin)
+            .collect();
+        parts.sort_by_key(|s| s.start);
+        let Some((keep, absorbed)) = parts.windows(2).find_map(|w| {
+            let same = w[0].end == w[1].start
+                && w[0].assigned == w[1].assigned
+                && w[0].locked == w[1].locked;
+            same.then(|| (w[0].id.clone(), w[1].id.clone()))
+        }) else {
+            return Ok(merged);
+        };
+        let pos =
+            util::find_shift_index(&scheduler.roster.shifts, &absorbed).expect("listed above");
+        let end = scheduler.roster.shifts[pos].end;
+        scheduler
+            .roster
+            .find_shift_mut(&keep)
+            .expect("listed above")
+            .end = end;
+        absorb(scheduler, pos, &keep);
+        merged += 1;
+    }
+}
+
+/// Supprime le shift d'indice `pos`, ses remplacements et les propositions
+/// d'échange qui le citent passant à `into`.
+fn absorb(scheduler: &mut Scheduler, pos: usize, into: &ShiftId) {
+    let removed = scheduler.roster.shifts.remove(pos);
+    for o in &mut scheduler.roster.overrides {
+        if o.shift_id == removed.id {
+            o.shift_id = into.clone();
+        }
+    }
+    for request in &mut scheduler.roster.swap_requests {
+        for ids in [&mut request.offered, &mut request.requested] {
+            if ids.contains(&removed.id) {
+                ids.retain(|id| id != &removed.id && id != into);
+                ids.push(into.clone());
+            }
+        }
+    }
+}
+
+pub(super) fn add_override(
+    scheduler: &mut Scheduler,
+    shift_id: &ShiftId,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    person: &PersonId,
+    opts: AssignOptions,
+) -> Result<(), SchedError> {
+    if end <= start {
+        return Err(SchedError::InvalidTimeRange);
+    }
+    let shift = scheduler
+        .roster
+        .find_shift(shift_id)
+        .ok_or_else(|| SchedError::UnknownShift(shift_id.as_str().to_string()))?;
+    if shift.locked {
+        return Err(SchedError::CoverInvalid("shift locked"));
+    }
+    if start < shift.start || end > shift.end {
+        return Err(SchedError::CoverInvalid("override outside shift"));
+    }
+    if shift.assigned.as_ref() == Some(person) {
+        return Err(SchedError::CoverInvalid("person already holds the shift"));
+    }
+    if scheduler.roster.find_person_by_id(person).is_none() {
+        return Err(SchedError::UnknownPerson(person.as_str().to_string()));
+    }
+    // Mêmes règles qu'un cover, appliquées à la seule portion remplacée.
+    let portion = Shift {
+        start,
+        end,
+        assigned: None,
+        ..shift.clone()
+    };
+    if !scheduler.person_ok_for_shift(person, &portion, opts, None) {
+        return Err(SchedError::CoverInvalid("assignment constraints violated"));
+    }
+
+    let from = shift.assigned.clone();
+    scheduler.roster.overrides.push(Override {
+        shift_id: shift_id.clone(),
+        start,
+        end,
+        person: person.clone(),
+    });
+    scheduler.roster.audit.push(AuditEntry {
+        at: Utc::now(),
+        shift: shift_id.clone(),
+        action: AuditAction::Override {
+            start,
+            end,
+            from,
+            to: person.clone(),
+        },
+        forced: Vec::new(),
+    });
+    Ok(())
+}
+
+/// Segment [from, fin) du shift, non assigné, héritant du verrou.
+pub(super) fn cover_segment(original: &Shift, from: DateTime<Utc>) -> Result<Shift, SchedError> {
     if from <= original.start {
         return Err(SchedError::CoverInvalid("cover point before shift start"));
     }
     if from >= original.end {
         return Err(SchedError::CoverInvalid("cover point after shift end"));
     }
-
-    let mut new_segment = Shift {
+    Ok(Shift {
         id: ShiftId::random(),
         name: original.name.clone(),
         start: from,
         end: original.end,
         role: original.role.clone(),
+        team: original.team.clone(),
         assigned: None,
-    };
+        locked: original.locked,
+        parent: Some(original.origin().clone()),
+        rotation: original.rotation.clone(),
+    })
+}
 
-    if cover
-        .vacations
-        .iter()
-        .any(|vac| util::vacation_blocks_shift(vac, &new_segment, opts))
+/// Tronque le shift d'indice `pos` au début du segment, insère le segment
+/// assigné à `person` juste après et trace la couverture. Les remplacements
+/// postérieurs au point de reprise passent au segment (coupés s'ils le chevauchent).
+pub(super) fn split_for_cover(
+    scheduler: &mut Scheduler,
+    pos: usize,
+    mut segment: Shift,
+    person: &PersonId,
+    forced: Vec<String>,
+) -> ShiftId {
+    let original = &mut scheduler.roster.shifts[pos];
+    let cut = segment.start;
+    original.end = cut;
+    let shift_id = original.id.clone();
+    let from = original.assigned.clone();
+
+    segment.assigned = Some(person.clone());
+    let new_id = segment.id.clone();
+    scheduler.roster.shifts.insert(pos + 1, segment);
+
+    let mut moved = Vec::new();
+    for o in scheduler
+        .roster
+        .overrides
+        .iter_mut()
+        .filter(|o| o.shift_id == shift_id && o.end > cut)
     {
-        return Err(SchedError::CoverInvalid("person vacation conflicts"));
+        if o.start >= cut {
+            o.shift_id = new_id.clone();
+        } else {
+            moved.push(Override {
+                shift_id: new_id.clone(),
+                start: cut,
+                ..o.clone()
+            });
+            o.end = cut;
+        }
     }
+    scheduler.roster.overrides.extend(moved);
 
-    if !scheduler.person_ok_for_shift(person, &new_segment, opts, None) {
-        return Err(SchedError::CoverInvalid("assignment constraints violated"));
+    scheduler.roster.audit.push(AuditEntry {
+        at: Utc::now(),
+        shift: shift_id,
+        action: AuditAction::Cover {
+            segment: new_id.clone(),
+            from,
+            to: person.clone(),
+        },
+        forced,
+    });
+    new_id
+}
+
+pub(super) fn offboard(
+    scheduler: &mut Scheduler,
+    person: &PersonId,
+    from: DateTime<Utc>,
+    people: &[Person],
+    opts: AssignOptions,
+) -> Result<OffboardReport, SchedError> {
+    let leaving = scheduler
+        .roster
+        .find_person_mut_by_id(person)
+        .ok_or_else(|| SchedError::UnknownPerson(person.as_str().to_string()))?;
+    leaving.status = PersonStatus::Archived;
+    leaving.active_until = Some(from);
+
+    // Les shifts commencés avant `from` (historique, shift en cours) sont conservés ;
+    // les shifts futurs verrouillés à son nom sont déverrouillés et redistribués.
+    let mut indices: Vec<usize> = scheduler
+        .roster
+        .shifts
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.assigned.as_ref() == Some(person) && s.start >= from)
+        .map(|(idx, _)| idx)
+        .collect();
+    indices.sort_by_key(|&idx| scheduler.roster.shifts[idx].start);
+    for &idx in &indices {
+        scheduler.roster.shifts[idx].assigned = None;
+        scheduler.roster.shifts[idx].locked = false;
     }
 
-    scheduler.roster.shifts[pos].end = from;
+    // Ses remplacements futurs sont supprimés ; celui en cours s'arrête à `from`.
+    let mut removed_overrides = Vec::new();
+    let mut kept = Vec::new();
+    for mut o in std::mem::take(&mut scheduler.roster.overrides) {
+        if &o.person != person || o.end <= from {
+            kept.push(o);
+        } else if o.start >= from {
+            removed_overrides.push(o);
+        } else {
+            o.end = from;
+            kept.push(o);
+        }
+    }
+    scheduler.roster.overrides = kept;
 
-    new_segment.assigned = Some(person.clone());
-    let new_id = new_segment.id.clone();
-    scheduler.roster.shifts.insert(pos + 1, new_segment);
+    let pool: Vec<Person> = people.iter().filter(|p| &p.id != person).cloned().collect();
+    assignment::fill_shifts(scheduler, &pool, opts, &indices, 0)?;
 
-    Ok(new_id)
+    let mut report = OffboardReport {
+        removed_overrides,
+        ..OffboardReport::default()
+    };
+    for idx in indices {
+        let shift = &scheduler.roster.shifts[idx];
+        report.unassigned.push(shift.id.clone());
+        if let Some(pid) = &shift.assigned {
+            report.reassigned.push((shift.id.clone(), pid.clone()));
+        }
+    }
+    Ok(report)
 }
@@ -0,0 +1,131 @@
+use super::{transaction, AssignOptions, SchedError, Scheduler};
+use crate::model::{PersonId, ShiftId, SwapRequest, SwapRequestId, SwapStatus};
+use chrono::{DateTime, Utc};
+
+pub(super) fn propose(
+    scheduler: &mut Scheduler,
+    from: &PersonId,
+    to: &PersonId,
+    offered: Vec<ShiftId>,
+    requested: Vec<ShiftId>,
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<SwapRequestId, SchedError> {
+    if from == to {
+        return Err(SchedError::TradeInvalid("cannot trade with oneself"));
+    }
+    for pid in [from, to] {
+        if scheduler.roster.find_person_by_id(pid).is_none() {
+            return Err(SchedError::UnknownPerson(pid.as_str().to_string()));
+        }
+    }
+    if offered.is_empty() {
+        return Err(SchedError::TradeInvalid("no shift offered"));
+    }
+    let mut seen: Vec<&ShiftId> = Vec::new();
+    for id in offered.iter().chain(&requested) {
+        if seen.contains(&id) {
+            return Err(SchedError::TradeInvalid("shift listed twice"));
+        }
+        seen.push(id);
+    }
+    check_holder(scheduler, &offered, from)?;
+    check_holder(scheduler, &requested, to)?;
+    let now = Utc::now();
+    if expires_at.is_some_and(|exp| exp <= now) {
+        return Err(SchedError::TradeInvalid("expiry already passed"));
+    }
+
+    let id = SwapRequestId::random();
+    scheduler.roster.swap_requests.push(SwapRequest {
+        id: id.clone(),
+        from: from.clone(),
+        to: to.clone(),
+        offered,
+        requested,
+        created_at: now,
+        expires_at,
+        status: SwapStatus::Pending,
+    });
+    Ok(id)
+}
+
+/// Applique tous les échanges de la proposition dans une transaction :
+/// seul l'état final est validé, et rien n'est modifié en cas d'échec.
+pub(super) fn accept(
+    scheduler: &mut Scheduler,
+    id: &SwapRequestId,
+    opts: AssignOptions,
+) -> Result<(), SchedError> {
+    let pos = request_index(scheduler, id)?;
+    let request = scheduler.roster.swap_requests[pos].clone();
+    if request.status != SwapStatus::Pending {
+        return Err(SchedError::TradeInvalid("request not pending"));
+    }
+    if !request.is_open(Utc::now()) {
+        return Err(SchedError::TradeInvalid("request expired"));
+    }
+    // Le planning a pu changer depuis la proposition.
+    check_holder(scheduler, &request.offered, &request.from)?;
+    check_holder(scheduler, &request.requested, &request.to)?;
+
+    transaction::run(scheduler, opts, |tx| {
+        for sid in request.offered.iter().chain(&request.requested) {
+            tx.swap(sid, &request.from, &request.to)?;
+        }
+        Ok(())
+    })?;
+    scheduler.roster.swap_requests[pos].status = SwapStatus::Accepted;
+    Ok(())
+}
+
+pub(super) fn reject(scheduler: &mut Scheduler, id: &SwapRequestId) -> Result<(), SchedError> {
+    let pos = request_index(scheduler, id)?;
+    let request = &mut scheduler.roster.swap_requests[pos];
+    if request.status != SwapStatus::Pending {
+        return Err(SchedError::TradeInvalid("request not pending"));
+    }
+    request.status = SwapStatus::Rejected;
+    Ok(())
+}
+
+pub(super) fn expire(scheduler: &mut Scheduler, now: DateTime<Utc>) -> Vec<SwapRequestId> {
+    let mut expired = Vec::new();
+    for request in &mut scheduler.roster.swap_requests {
+        if request.status == SwapStatus::Pending && !request.is_open(now) {
+            request.status = SwapStatus::Expired;
+            expired.push(request.id.clone());
+        }
+    }
+    expired
+}
+
+fn request_index(scheduler: &Scheduler, id: &SwapRequestId) -> Result<usize, SchedError> {
+    scheduler
+        .roster
+        .swap_requests
+        .iter()
+        .position(|r| &r.id == id)
+        .ok_or_else(|| SchedError::UnknownSwapRequest(id.as_str().to_string()))
+}
+
+fn check_holder(
+    scheduler: &Scheduler,
+    shifts: &[ShiftId],
+    holder: &PersonId,
+) -> Result<(), SchedError> {
+    for sid in shifts {
+        let shift = scheduler
+            .roster
+            .find_shift(sid)
+            .ok_or_else(|| SchedError::UnknownShift(sid.as_str().to_string()))?;
+        if shift.assigned.as_ref() != Some(holder) {
+            return Err(SchedError::TradeInvalid(
+                "shift not held by the trading person",
+            ));
+        }
+        if shift.locked {
+            return Err(SchedError::TradeInvalid("shift locked"));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use super::{conflicts, mutate, util, AssignOptions, ConflictKind, SchedError, Scheduler};
+use crate::model::{AuditAction, AuditEntry, PersonId, Roster, Shift, ShiftId, SwapStatus};
+use chrono::{DateTime, Utc};
+
+/// Lot de mutations appliquées sans validation intermédiaire.
+///
+/// Obtenu via [`Scheduler::transaction`] : les contraintes sont vérifiées une
+/// seule fois sur l'état final, et tout est annulé si l'une échoue.
+pub struct Transaction<'a> {
+    scheduler: &'a mut Scheduler,
+    /// Shifts dont l'assignation a changé, à revalider.
+    touched: Vec<ShiftId>,
+}
+
+impl Transaction<'_> {
+    /// État courant (mutations déjà appliquées comprises).
+    pub fn roster(&self) -> &Roster {
+        &self.scheduler.roster
+    }
+
+    /// Passe le shift de `a` à `b` (ou de `b` à `a`).
+    pub fn swap(
+        &mut self,
+        shift_id: &ShiftId,
+        a: &PersonId,
+        b: &PersonId,
+    ) -> Result<(), SchedError> {
+        let pos = self.unlocked_index(shift_id)?;
+        let target = mutate::swap_target(&self.scheduler.roster.shifts[pos], a, b)?;
+        let from = self.scheduler.roster.shifts[pos]
+            .assigned
+            .replace(target.clone())
+            .expect("swap_target requires an assignee");
+        self.record(shift_id, AuditAction::Swap { from, to: target });
+        Ok(())
+    }
+
+    /// Fait couvrir la fin du shift par `person` à partir de `from`.
+    pub fn cover(
+        &mut self,
+        shift_id: &ShiftId,
+        from: DateTime<Utc>,
+        person: &PersonId,
+    ) -> Result<ShiftId, SchedError> {
+        let pos = self.unlocked_index(shift_id)?;
+        let segment = mutate::cover_segment(&self.scheduler.roster.shifts[pos], from)?;
+        let id = mutate::split_for_cover(self.scheduler, pos, segment, person, Vec::new());
+        self.touched.push(id.clone());
+        Ok(id)
+    }
+
+    /// Attribue le shift à `person`, ou le libère.
+    pub fn reassign(
+        &mut self,
+        shift_id: &ShiftId,
+        person: Option<&PersonId>,
+    ) -> Result<(), SchedError> {
+        let pos = self.unlocked_index(shift_id)?;
+        let to = person.cloned();
+        let from = std::mem::replace(&mut self.scheduler.roster.shifts[pos].assigned, to.clone());
+        if from != to {
+            self.record(shift_id, AuditAction::Reassign { from, to });
+        }
+        Ok(())
+    }
+
+    pub fn create_shift(
+        &mut self,
+        name: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<ShiftId, SchedError> {
+        self.scheduler.create_shift(name, start, end)
+    }
+
+    /// Supprime un shift et le renvoie. Ses remplacements disparaissent avec
+    /// lui et les propositions d'échange en attente qui le citent expirent.
+    pub fn delete_shift(&mut self, shift_id: &ShiftId) -> Result<Shift, SchedError> {
+        let pos = self.unlocked_index(shift_id)?;
+        self.touched.retain(|id| id != shift_id);
+        let roster = &mut self.scheduler.roster;
+        roster.overrides.retain(|o| &o.shift_id != shift_id);
+        for request in &mut roster.swap_requests {
+            let mut cited = request.offered.iter().chain(&request.requested);
+            if request.status == SwapStatus::Pending && cited.any(|id| id == shift_id) {
+                request.status = SwapStatus::Expired;
+            }
+        }
+        Ok(roster.shifts.remove(pos))
+    }
+
+    fn unlocked_index(&self, shift_id: &ShiftId) -> Result<usize, SchedError> {
+        let pos = util::find_shift_index(&self.scheduler.roster.shifts, shift_id)
+            .ok_or_else(|| SchedError::UnknownShift(shift_id.as_str().to_string()))?;
+        if self.scheduler.roster.shifts[pos].locked {
+            return Err(SchedError::TransactionInvalid(format!(
+                "shift {} locked",
+                shift_id.as_str()
+            )));
+        }
+        Ok(pos)
+    }
+
+    fn record(&mut self, shift_id: &ShiftId, action: AuditAction) {
+        self.scheduler.roster.audit.push(AuditEntry {
+            at: Utc::now(),
+            shift: shift_id.clone(),
+            action,
+            forced: Vec::new(),
+        });
+        self.touched.push(shift_id.clone());
+    }
+}
+
+pub(super) fn run<T, F>(
+    scheduler: &mut Scheduler,
+    opts: AssignOptions,
+    f: F,
+) -> Result<T, SchedError>
+where
+    F: FnOnce(&mut Transaction<'_>) -> Result<T, SchedError>,
+{
+    let snapshot = scheduler.roster.clone();
+    let before = conflict_keys(scheduler, opts);
+    let mut tx = Transaction {
+        scheduler: &mut *scheduler,
+        touched: Vec::new(),
+    };
+    let result = f(&mut tx).and_then(|value| {
+        let touched = std::mem::take(&mut tx.touched);
+        validate(tx.scheduler, &touched, &before, opts).map(|()| value)
+    });
+    if result.is_err() {
+        scheduler.roster = snapshot;
+    }
+    result
+}
+
+type ConflictKey = (PersonId, ShiftId, ShiftId, ConflictKind);
+
+fn conflict_keys(scheduler: &Scheduler, opts: AssignOptions) -> Vec<ConflictKey> {
+    conflicts::detect_conflicts(scheduler, opts)
+        .into_iter()
+        .map(|c| (c.person, c.shift_a, c.shift_b, c.kind))
+        .collect()
+}
+
+/// Valide l'état final entier : règles d'assignation complètes pour chaque
+/// shift réattribué, références des remplacements et propositions en attente,
+/// puis conflits de tout le planning. Seuls les conflits absents avant la
+/// transaction sont refusés.
+fn validate(
+    scheduler: &Scheduler,
+    touched: &[ShiftId],
+    before: &[ConflictKey],
+    opts: AssignOptions,
+) -> Result<(), SchedError> {
+    let roster = &scheduler.roster;
+    for id in touched {
+        let Some(pos) = util::find_shift_index(&roster.shifts, id) else {
+            continue;
+        };
+        let shift = &roster.shifts[pos];
+        let Some(pid) = &shift.assigned else {
+            continue;
+        };
+        if roster.find_person_by_id(pid).is_none() {
+            return Err(SchedError::UnknownPerson(pid.as_str().to_string()));
+        }
+        if !scheduler.person_ok_for_shift(pid, shift, opts, Some(pos)) {
+            return Err(SchedError::TransactionInvalid(format!(
+                "shift {}: assignment constraints violated for {}",
+                id.as_str(),
+                pid.as_str()
+            )));
+        }
+    }
+
+    for o in &roster.overrides {
+        if roster.find_shift(&o.shift_id).is_none() {
+            return Err(SchedError::TransactionInvalid(format!(
+                "override on missing shift {}",
+                o.shift_id.as_str()
+            )));
+        }
+        if roster.find_person_by_id(&o.person).is_none() {
+            return Err(SchedError::UnknownPerson(o.person.as_str().to_string()));
+        }
+    }
+    for request in roster
+        .swap_requests
+        .iter()
+        .filter(|r| r.status == SwapStatus::Pending)
+    {
+        let mut cited = request.offered.iter().chain(&request.requested);
+        if let Some(missing) = cited.find(|id| roster.find_shift(id).is_none()) {
+            return Err(SchedError::TransactionInvalid(format!(
+                "swap request {} cites missing shift {}",
+                request.id.as_str(),
+                missing.as_str()
+            )));
+        }
+    }
+
+    let after = conflict_keys(scheduler, opts);
+    if let Some((person, a, b, kind)) = after.iter().find(|c| !before.contains(c)) {
+        return Err(SchedError::TransactionInvalid(format!(
+            "{} conflict for {} between {} and {}",
+            kind.as_str(),
+            person.as_str(),
+            a.as_str(),
+            b.as_str()
+        )));
+    }
+    Ok(())
+}
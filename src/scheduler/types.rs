@@ -1,27 +1,57 @@
-use crate::model::{PersonId, ShiftId};
+use crate::model::{Override, PersonId, Shift, ShiftId};
+use chrono::{DateTime, Utc};
 use thiserror::Error;
 
-/// Options d'assignation
-#[derive(Debug, Clone, Copy)]
-pub struct AssignOptions {
-    pub min_rest_hours: u32,
-    pub max_consecutive_shifts: u32,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictKind {
+    Overlap,
+    DoubleAssignment,
+    RestViolation,
+    /// Plus de shifts dans le mois que `max_shifts_per_month`.
+    MonthlyShiftLimit,
+    /// Plus d'heures sur 7 jours glissants que `max_hours_per_7_days`.
+    WeeklyHoursLimit,
+    /// Plus de week-ends dans le trimestre que `max_weekends_per_quarter`.
+    QuarterlyWeekendLimit,
 }
 
-impl Default for AssignOptions {
-    fn default() -> Self {
-        Self {
-            min_rest_hours: 11,
-            max_consecutive_shifts: 3,
+impl ConflictKind {
+    /// Libellé court (rapports CSV, journal d'audit).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConflictKind::Overlap => "overlap",
+            ConflictKind::DoubleAssignment => "double",
+            ConflictKind::RestViolation => "rest",
+            ConflictKind::MonthlyShiftLimit => "month_limit",
+            ConflictKind::WeeklyHoursLimit => "hours_limit",
+            ConflictKind::QuarterlyWeekendLimit => "weekend_limit",
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum ConflictKind {
+/// Défaut de couverture d'une rotation « follow-the-sun ».
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverageKind {
+    /// Personne n'est de garde entre deux passations.
+    Gap,
+    /// Deux régions sont de garde en même temps.
     Overlap,
-    DoubleAssignment,
-    RestViolation,
+}
+
+impl CoverageKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CoverageKind::Gap => "gap",
+            CoverageKind::Overlap => "overlap",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageIssue {
+    pub kind: CoverageKind,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone)]
@@ -32,6 +62,44 @@ pub struct Conflict {
     pub kind: ConflictKind,
 }
 
+/// Périmètre d'une réassignation incrémentale.
+///
+/// Les shifts hors périmètre gardent leur assignation et servent de
+/// contraintes (repos, chevauchements, limites).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AssignScope {
+    /// Ne remplir que les shifts sans assignation.
+    pub only_unassigned: bool,
+    /// Shifts se terminant après cet instant.
+    pub from: Option<DateTime<Utc>>,
+    /// Shifts commençant avant cet instant.
+    pub until: Option<DateTime<Utc>>,
+    /// Les shifts commençant avant cet instant (passés ou en cours) ne sont jamais modifiés.
+    /// Les shifts verrouillés sont toujours hors périmètre.
+    pub now: Option<DateTime<Utc>>,
+}
+
+impl AssignScope {
+    pub fn contains(&self, shift: &Shift) -> bool {
+        !(shift.locked || self.only_unassigned && shift.assigned.is_some())
+            && self.from.map_or(true, |from| shift.end > from)
+            && self.until.map_or(true, |until| shift.start < until)
+            && self.now.map_or(true, |now| shift.start >= now)
+    }
+}
+
+/// Résultat d'un offboarding.
+#[derive(Debug, Clone, Default)]
+pub struct OffboardReport {
+    /// Shifts futurs retirés à la personne.
+    pub unassigned: Vec<ShiftId>,
+    /// Parmi eux, ceux réassignés (et à qui).
+    pub reassigned: Vec<(ShiftId, PersonId)>,
+    /// Remplacements futurs de la personne supprimés : le titulaire du shift
+    /// reprend la portion.
+    pub removed_overrides: Vec<Override>,
+}
+
 #[derive(Error, Debug)]
 pub enum SchedError {
     #[error("invalid time range: end must be after start")]
@@ -44,6 +112,18 @@ pub enum SchedError {
     SwapInvalid(&'static str),
     #[error("cover invalid: {0}")]
     CoverInvalid(&'static str),
+    #[error("unknown team: {0}")]
+    UnknownTeam(String),
+    #[error("unknown follow-the-sun rotation: {0}")]
+    UnknownRotation(String),
+    #[error("unknown swap request: {0}")]
+    UnknownSwapRequest(String),
+    #[error("unknown override #{1} on shift {0}")]
+    UnknownOverride(String, usize),
+    #[error("swap request invalid: {0}")]
+    TradeInvalid(&'static str),
+    #[error("transaction rolled back: {0}")]
+    TransactionInvalid(String),
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -1,6 +1,7 @@
 use super::AssignOptions;
-use crate::model::{Shift, ShiftId, VacationPeriod};
+use crate::model::{PersonId, Roster, Shift, ShiftId, VacationPeriod};
 use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
 
 pub(super) fn overlaps(
     a_start: DateTime<Utc>,
@@ -25,3 +26,36 @@ pub(super) fn vacation_blocks_shift(
 pub(super) fn find_shift_index(shifts: &[Shift], shift_id: &ShiftId) -> Option<usize> {
     shifts.iter().position(|s| &s.id == shift_id)
 }
+
+/// Portions de shifts effectivement tenues par `person`, remplacements
+/// compris : une copie du shift (même id) par segment, triées par début.
+/// Le shift d'indice `exclude` est ignoré.
+pub(super) fn held_by(roster: &Roster, person: &PersonId, exclude: Option<usize>) -> Vec<Shift> {
+    let overridden: HashSet<&ShiftId> = roster
+        .overrides
+        .iter()
+        .filter(|o| &o.person == person)
+        .map(|o| &o.shift_id)
+        .collect();
+    let mut out: Vec<Shift> = roster
+        .shifts
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| exclude != Some(*idx))
+        .filter(|(_, s)| s.assigned.as_ref() == Some(person) || overridden.contains(&s.id))
+        .flat_map(|(_, s)| {
+            roster
+                .segments(s)
+                .into_iter()
+                .filter(|seg| seg.holder == Some(person))
+                .map(|seg| Shift {
+                    start: seg.start,
+                    end: seg.end,
+                    ..s.clone()
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    out.sort_by_key(|s| s.start);
+    out
+}
@@ -0,0 +1,346 @@
+use crate::holiday::HolidayCalendar;
+use crate::model::{AuditAction, Person, PersonId, Roster, Shift, ShiftId};
+use crate::tz::Zone;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, Utc, Weekday};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::str::FromStr;
+
+/// Granularité des périodes de regroupement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Period {
+    #[default]
+    Month,
+    Quarter,
+    Year,
+    /// Une seule période couvrant toute la fenêtre.
+    All,
+}
+
+impl Period {
+    /// Libellé de la période contenant `date` (`2025-10`, `2025-Q4`, `2025`, `all`).
+    pub fn label(&self, date: NaiveDate) -> String {
+        match self {
+            Period::Month => format!("{:04}-{:02}", date.year(), date.month()),
+            Period::Quarter => format!("{:04}-Q{}", date.year(), (date.month() - 1) / 3 + 1),
+            Period::Year => format!("{:04}", date.year()),
+            Period::All => "all".to_string(),
+        }
+    }
+
+    /// Premier jour de la période contenant `date` et premier jour de la
+    /// suivante ; `None` pour [`Period::All`].
+    pub fn range(&self, date: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        let (year, month, months) = match self {
+            Period::Month => (date.year(), date.month(), 1),
+            Period::Quarter => (date.year(), (date.month() - 1) / 3 * 3 + 1, 3),
+            Period::Year => (date.year(), 1, 12),
+            Period::All => return None,
+        };
+        let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+        Some((start, start.checked_add_months(Months::new(months))?))
+    }
+}
+
+impl FromStr for Period {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "month" => Ok(Period::Month),
+            "quarter" => Ok(Period::Quarter),
+            "year" => Ok(Period::Year),
+            "all" => Ok(Period::All),
+            _ => Err(format!("unknown period: {s}")),
+        }
+    }
+}
+
+/// Paramètres du calcul de statistiques.
+#[derive(Debug, Clone)]
+pub struct StatsOptions {
+    pub period: Period,
+    /// Fuseau utilisé pour les jours, week-ends, nuits et périodes.
+    pub zone: Zone,
+    /// Début de la plage de nuit (heure locale).
+    pub night_start: NaiveTime,
+    /// Fin de la plage de nuit (heure locale, le lendemain si <= `night_start`).
+    pub night_end: NaiveTime,
+    pub holidays: HolidayCalendar,
+    /// Shifts se terminant après cet instant.
+    pub from: Option<DateTime<Utc>>,
+    /// Shifts commençant avant cet instant.
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl Default for StatsOptions {
+    fn default() -> Self {
+        Self {
+            period: Period::Month,
+            zone: Zone::Utc,
+            night_start: NaiveTime::from_hms_opt(22, 0, 0).expect("valid time"),
+            night_end: NaiveTime::from_hms_opt(6, 0, 0).expect("valid time"),
+            holidays: HolidayCalendar::new(),
+            from: None,
+            until: None,
+        }
+    }
+}
+
+impl StatsOptions {
+    fn in_window(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        self.from.map_or(true, |from| end > from) && self.until.map_or(true, |until| start < until)
+    }
+}
+
+/// Charge d'une personne sur une période.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PersonStats {
+    pub period: String,
+    pub handle: String,
+    pub display_name: String,
+    /// Équipe servant de référence aux écarts (la première qui compte la
+    /// personne parmi ses membres).
+    pub team: Option<String>,
+    pub shifts: u32,
+    pub hours: f64,
+    pub night_hours: f64,
+    /// Shifts touchant un samedi ou un dimanche.
+    pub weekend_shifts: u32,
+    /// Shifts touchant un jour férié.
+    pub holiday_shifts: u32,
+    /// Segments couverts (ou remplacements) pour quelqu'un d'autre.
+    pub covers_given: u32,
+    /// Segments de ses shifts couverts (ou remplacés) par quelqu'un d'autre.
+    pub covers_received: u32,
+    pub swaps: u32,
+    /// Écart d'heures à la moyenne de l'équipe (`team`) sur la période.
+    pub hours_deviation: f64,
+    /// Écart de nombre de shifts à la moyenne de l'équipe (`team`) sur la période.
+    pub shifts_deviation: f64,
+}
+
+/// Calcule les statistiques par personne et par période.
+///
+/// Les heures sont comptées par segment (voir [`Roster::segments`]) : la plage
+/// d'un remplacement ponctuel est créditée à son titulaire et non à la
+/// personne assignée. Chaque période présente contient une ligne par personne
+/// active dont la participation recoupe la période (même sans shift), plus
+/// une ligne pour toute personne ayant une activité sur la période (un membre
+/// archivé depuis, par exemple). Les écarts sont calculés par rapport à la
+/// moyenne de ces lignes, équipe par équipe quand le roster en définit ; les
+/// personnes hors équipe forment un groupe à part.
+pub fn compute(roster: &Roster, opts: &StatsOptions) -> Vec<PersonStats> {
+    let mut periods: BTreeMap<String, PeriodRows> = BTreeMap::new();
+    let index_of = |pid: &PersonId| roster.people.iter().position(|p| &p.id == pid);
+    let mut counted: HashSet<(String, usize, &ShiftId, &str)> = HashSet::new();
+
+    for shift in &roster.shifts {
+        for segment in roster.segments(shift) {
+            if !opts.in_window(segment.start, segment.end) {
+                continue;
+            }
+            let Some(idx) = segment.holder.and_then(index_of) else {
+                continue;
+            };
+            let held = Shift {
+                start: segment.start,
+                end: segment.end,
+                ..shift.clone()
+            };
+            let dates = opts.zone.local_dates(held.start, held.end);
+            let night = night_minutes(&held, opts);
+            let (label, date) = period_of(held.start, opts);
+            // Plusieurs segments d'un même shift logique tenus par la même
+            // personne comptent pour un seul shift.
+            let mut first = |what| counted.insert((label.clone(), idx, shift.origin(), what));
+            let new_shift = first("shift");
+            let weekend = dates
+                .iter()
+                .any(|d| matches!(d.weekday(), Weekday::Sat | Weekday::Sun))
+                && first("weekend");
+            let holiday = dates.iter().any(|d| opts.holidays.contains(*d)) && first("holiday");
+            let r = row(&mut periods, roster, label, date, idx);
+            r.shifts += u32::from(new_shift);
+            r.hours += held.duration_minutes() as f64 / 60.0;
+            r.night_hours += night as f64 / 60.0;
+            r.weekend_shifts += u32::from(weekend);
+            r.holiday_shifts += u32::from(holiday);
+        }
+    }
+
+    for entry in &roster.audit {
+        let (start, end) = match &entry.action {
+            AuditAction::Override { start, end, .. } => (*start, *end),
+            action => {
+                let touched = match action {
+                    AuditAction::Cover { segment, .. } => roster.find_shift(segment),
+                    _ => roster.find_shift(&entry.shift),
+                };
+                touched.map_or((entry.at, entry.at), |s| (s.start, s.end))
+            }
+        };
+        if !opts.in_window(start, end) {
+            continue;
+        }
+        let (label, date) = period_of(start, opts);
+        match &entry.action {
+            AuditAction::Swap { from, to } => {
+                for pid in [from, to] {
+                    if let Some(idx) = index_of(pid) {
+                        row(&mut periods, roster, label.clone(), date, idx).swaps += 1;
+                    }
+                }
+            }
+            AuditAction::Cover { from, to, .. } | AuditAction::Override { from, to, .. } => {
+                if let Some(idx) = index_of(to) {
+                    row(&mut periods, roster, label.clone(), date, idx).covers_given += 1;
+                }
+                if let Some(idx) = from.as_ref().and_then(index_of) {
+                    row(&mut periods, roster, label.clone(), date, idx).covers_received += 1;
+                }
+            }
+            AuditAction::Reassign { .. } | AuditAction::Uncover { .. } => {}
+        }
+    }
+
+    let mut out = Vec::new();
+    for period in periods.into_values() {
+        let (lo, hi) = period_bounds(period.date, opts);
+        let mut rows: Vec<PersonStats> = period
+            .rows
+            .into_iter()
+            .zip(&roster.people)
+            .zip(period.touched)
+            .filter(|((_, p), touched)| *touched || (p.is_active() && overlaps(p, lo, hi)))
+            .map(|((r, _), _)| r)
+            .collect();
+        let mut means: BTreeMap<Option<String>, (f64, f64, f64)> = BTreeMap::new();
+        for r in &rows {
+            let m = means.entry(r.team.clone()).or_default();
+            m.0 += 1.0;
+            m.1 += r.hours;
+            m.2 += f64::from(r.shifts);
+        }
+        for r in rows.iter_mut() {
+            let (n, hours, shifts) = means[&r.team];
+            r.hours_deviation = round2(r.hours - hours / n);
+            r.shifts_deviation = round2(f64::from(r.shifts) - shifts / n);
+            r.hours = round2(r.hours);
+            r.night_hours = round2(r.night_hours);
+        }
+        out.extend(rows);
+    }
+    out
+}
+
+/// Lignes d'une période, une par personne du roster, avant filtrage.
+struct PeriodRows {
+    /// Un jour quelconque de la période, pour en retrouver les bornes.
+    date: NaiveDate,
+    rows: Vec<PersonStats>,
+    /// Personnes ayant une activité comptée sur la période.
+    touched: Vec<bool>,
+}
+
+fn row<'a>(
+    periods: &'a mut BTreeMap<String, PeriodRows>,
+    roster: &Roster,
+    label: String,
+    date: NaiveDate,
+    idx: usize,
+) -> &'a mut PersonStats {
+    let period = periods.entry(label.clone()).or_insert_with(|| PeriodRows {
+        date,
+        rows: roster
+            .people
+            .iter()
+            .map(|p| PersonStats {
+                period: label.clone(),
+                handle: p.handle.clone(),
+                display_name: p.display_name.clone(),
+                team: roster
+                    .teams
+                    .iter()
+                    .find(|t| t.members.contains(&p.id))
+                    .map(|t| t.name.clone()),
+                shifts: 0,
+                hours: 0.0,
+                night_hours: 0.0,
+                weekend_shifts: 0,
+                holiday_shifts: 0,
+                covers_given: 0,
+                covers_received: 0,
+                swaps: 0,
+                hours_deviation: 0.0,
+                shifts_deviation: 0.0,
+            })
+            .collect(),
+        touched: vec![false; roster.people.len()],
+    });
+    period.touched[idx] = true;
+    &mut period.rows[idx]
+}
+
+fn period_of(at: DateTime<Utc>, opts: &StatsOptions) -> (String, NaiveDate) {
+    let date = opts.zone.naive_local(at).date();
+    (opts.period.label(date), date)
+}
+
+/// Bornes UTC de la période contenant `date` ; la fenêtre demandée pour
+/// [`Period::All`].
+fn period_bounds(
+    date: NaiveDate,
+    opts: &StatsOptions,
+) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+    match opts.period.range(date) {
+        Some((start, end)) => {
+            let midnight = |d: NaiveDate| opts.zone.from_local(d.and_time(NaiveTime::MIN));
+            (midnight(start), midnight(end))
+        }
+        None => (opts.from, opts.until),
+    }
+}
+
+/// Vrai si la participation de `person` recoupe [lo, hi) (bornes absentes :
+/// non limité).
+fn overlaps(person: &Person, lo: Option<DateTime<Utc>>, hi: Option<DateTime<Utc>>) -> bool {
+    person
+        .active_from
+        .zip(hi)
+        .map_or(true, |(from, hi)| from < hi)
+        && person
+            .active_until
+            .zip(lo)
+            .map_or(true, |(until, lo)| until > lo)
+}
+
+fn round2(x: f64) -> f64 {
+    (x * 100.0).round() / 100.0
+}
+
+/// Minutes du shift tombant dans la plage de nuit locale.
+fn night_minutes(shift: &Shift, opts: &StatsOptions) -> i64 {
+    let first = opts.zone.naive_local(shift.start).date() - Duration::days(1);
+    let last = opts.zone.naive_local(shift.end).date();
+    let mut total = 0;
+    for day in first.iter_days().take_while(|d| *d <= last) {
+        let end_day = if opts.night_end <= opts.night_start {
+            day + Duration::days(1)
+        } else {
+            day
+        };
+        let (Some(ws), Some(we)) = (
+            opts.zone.from_local(day.and_time(opts.night_start)),
+            opts.zone.from_local(end_day.and_time(opts.night_end)),
+        ) else {
+            continue;
+        };
+        let start = shift.start.max(ws);
+        let end = shift.end.min(we);
+        if end > start {
+            total += (end - start).num_minutes();
+        }
+    }
+    total
+}
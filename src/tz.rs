@@ -0,0 +1,140 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Fuseau utilisé pour afficher ou interpréter des heures locales.
+///
+/// La lib stocke tout en UTC ; ce type sert uniquement aux frontières
+/// (rendu, classification jour/nuit, saisie locale).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Zone {
+    #[default]
+    Utc,
+    /// Fuseau du système.
+    Local,
+    /// Fuseau IANA (`Europe/Paris`, `America/New_York`, ...).
+    Named(Tz),
+}
+
+impl Zone {
+    pub fn is_utc(&self) -> bool {
+        matches!(self, Zone::Utc)
+    }
+
+    /// Rendu RFC3339 dans le fuseau.
+    pub fn to_rfc3339(&self, dt: DateTime<Utc>) -> String {
+        match self {
+            Zone::Utc => dt.to_rfc3339(),
+            Zone::Local => dt.with_timezone(&Local).to_rfc3339(),
+            Zone::Named(tz) => dt.with_timezone(tz).to_rfc3339(),
+        }
+    }
+
+    /// Rendu selon un format `strftime`.
+    pub fn format(&self, dt: DateTime<Utc>, fmt: &str) -> String {
+        match self {
+            Zone::Utc => dt.format(fmt).to_string(),
+            Zone::Local => dt.with_timezone(&Local).format(fmt).to_string(),
+            Zone::Named(tz) => dt.with_timezone(tz).format(fmt).to_string(),
+        }
+    }
+
+    /// Date/heure murale correspondant à un instant UTC.
+    pub fn naive_local(&self, dt: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Zone::Utc => dt.naive_utc(),
+            Zone::Local => dt.with_timezone(&Local).naive_local(),
+            Zone::Named(tz) => dt.with_timezone(tz).naive_local(),
+        }
+    }
+
+    /// Instant UTC d'une heure murale ; `None` si elle n'existe pas (passage à l'heure d'été).
+    /// En cas d'ambiguïté (retour à l'heure d'hiver), la première occurrence est retenue.
+    pub fn from_local(&self, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Zone::Utc => Some(Utc.from_utc_datetime(&naive)),
+            Zone::Local => Local
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc)),
+            Zone::Named(tz) => tz
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc)),
+        }
+    }
+
+    /// Dates locales touchées par [start, end) (fin exclue).
+    pub fn local_dates(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<NaiveDate> {
+        let first = self.naive_local(start).date();
+        let last = self.naive_local(end - Duration::seconds(1)).date();
+        first.iter_days().take_while(|d| *d <= last).collect()
+    }
+
+    /// Découpe [start, end) aux minuits locaux : `(date locale, début, fin)` par morceau.
+    pub fn split_days(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Vec<(NaiveDate, DateTime<Utc>, DateTime<Utc>)> {
+        let mut out = Vec::new();
+        let mut cursor = start;
+        while cursor < end {
+            let date = self.naive_local(cursor).date();
+            let next_midnight = date
+                .succ_opt()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .and_then(|n| self.from_local(n))
+                .filter(|n| *n > cursor)
+                .unwrap_or(cursor + Duration::days(1));
+            let piece_end = next_midnight.min(end);
+            out.push((date, cursor, piece_end));
+            cursor = piece_end;
+        }
+        out
+    }
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Zone::Utc => f.write_str("UTC"),
+            Zone::Local => f.write_str("local"),
+            Zone::Named(tz) => f.write_str(tz.name()),
+        }
+    }
+}
+
+impl FromStr for Zone {
+    type Err = String;
+
+    /// `utc`, `local` ou un nom IANA.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "utc" | "z" => Ok(Zone::Utc),
+            "local" => Ok(Zone::Local),
+            _ => s
+                .trim()
+                .parse::<Tz>()
+                .map(Zone::Named)
+                .map_err(|_| format!("unknown time zone: {s}")),
+        }
+    }
+}
+
+impl TryFrom<String> for Zone {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Zone> for String {
+    fn from(zone: Zone) -> Self {
+        zone.to_string()
+    }
+}
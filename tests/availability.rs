@@ -0,0 +1,112 @@
+#![forbid(unsafe_code)]
+use astreinte::{io, AssignOptions, AvailabilityRule, Person, Scheduler, Zone};
+use chrono::{Duration, TimeZone, Utc};
+use std::io::Write;
+
+#[test]
+fn rules_parse_and_roundtrip() {
+    let rule: AvailabilityRule = "never:tue@night".parse().unwrap();
+    assert!(rule.kind.is_hard());
+    assert_eq!(rule.to_string(), "never:tue@20:00-08:00");
+
+    let only: AvailabilityRule = "only:weekend".parse().unwrap();
+    assert_eq!(only.to_string(), "only:sat,sun");
+    let avoid: AvailabilityRule = "avoid:mon-fri@08:00-18:00".parse().unwrap();
+    assert_eq!(avoid.days.len(), 5);
+
+    assert!("sometimes:mon".parse::<AvailabilityRule>().is_err());
+    assert!("never:".parse::<AvailabilityRule>().is_err());
+    assert!("never:xyz".parse::<AvailabilityRule>().is_err());
+
+    let json = serde_json::to_string(&rule).unwrap();
+    assert_eq!(json, "\"never:tue@20:00-08:00\"");
+}
+
+#[test]
+fn hard_rules_block_and_soft_rules_steer_assignment() {
+    let mut s = Scheduler::new();
+    let mut alice = Person::new("alice", "Alice");
+    let mut bob = Person::new("bob", "Bob");
+    let mut carol = Person::new("carol", "Carol");
+    // Alice ne fait jamais les mardis soir (garde d'enfants), Bob préfère les nuits
+    alice.availability = vec!["never:tue@night".parse().unwrap()];
+    bob.availability = vec!["prefer:@night".parse().unwrap()];
+    carol.availability = vec!["only:weekend".parse().unwrap()];
+    s.add_people(vec![alice.clone(), bob.clone(), carol.clone()]);
+    s.set_zone("Europe/Paris".parse::<Zone>().unwrap());
+
+    // Mardi 7 octobre 2025, 21:00 -> 07:00 heure de Paris
+    let tue = Utc.with_ymd_and_hms(2025, 10, 7, 19, 0, 0).unwrap();
+    let night = s
+        .create_shift("nuit", tue, tue + Duration::hours(10))
+        .unwrap();
+    s.assign_rotative(
+        &[alice.clone(), carol.clone(), bob.clone()],
+        AssignOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        s.roster().find_shift(&night).unwrap().assigned.as_ref(),
+        Some(&bob.id)
+    );
+
+    // Jeudi en journée : Carol (week-end uniquement) exclue, Bob n'y gagne rien,
+    // Alice passe en premier dans la rotation.
+    let thu = Utc.with_ymd_and_hms(2025, 10, 16, 7, 0, 0).unwrap();
+    let day = s
+        .create_shift("jour", thu, thu + Duration::hours(10))
+        .unwrap();
+    s.assign_rotative(
+        &[carol.clone(), alice.clone(), bob.clone()],
+        AssignOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        s.roster().find_shift(&day).unwrap().assigned.as_ref(),
+        Some(&alice.id)
+    );
+}
+
+#[test]
+fn soft_preference_beats_rotation_order() {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let mut bob = Person::new("bob", "Bob");
+    bob.availability = vec!["prefer:@night".parse().unwrap()];
+    s.add_people(vec![alice.clone(), bob.clone()]);
+
+    let wed = Utc.with_ymd_and_hms(2025, 10, 8, 20, 0, 0).unwrap();
+    let night = s
+        .create_shift("nuit", wed, wed + Duration::hours(10))
+        .unwrap();
+    s.assign_rotative(&[alice.clone(), bob.clone()], AssignOptions::default())
+        .unwrap();
+    assert_eq!(
+        s.roster().find_shift(&night).unwrap().assigned.as_ref(),
+        Some(&bob.id)
+    );
+}
+
+#[test]
+fn people_csv_accepts_availability_column() {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(
+        file,
+        "handle,display_name,on_vacation,vacations,availability"
+    )
+    .unwrap();
+    writeln!(file, "alice,Alice,false,,never:tue@night;prefer:weekend").unwrap();
+    writeln!(file, "bob,Bob,false,,").unwrap();
+    let people = io::import_people_csv(file.path()).unwrap();
+    assert_eq!(people[0].availability.len(), 2);
+    assert!(people[1].availability.is_empty());
+
+    let mut bad = tempfile::NamedTempFile::new().unwrap();
+    writeln!(
+        bad,
+        "handle,display_name,on_vacation,vacations,availability"
+    )
+    .unwrap();
+    writeln!(bad, "alice,Alice,false,,whenever").unwrap();
+    assert!(io::import_people_csv(bad.path()).is_err());
+}
@@ -0,0 +1,178 @@
+#![forbid(unsafe_code)]
+use assert_cmd::Command;
+use predicates::prelude::*;
+use predicates::str::contains;
+
+fn cli(roster: &std::path::Path) -> Command {
+    let mut cmd = Command::cargo_bin("asterinte-cli").unwrap();
+    cmd.arg("--roster").arg(roster);
+    cmd
+}
+
+#[test]
+fn vacation_add_reports_impacted_shifts() {
+    let dir = tempfile::tempdir().unwrap();
+    let roster = dir.path().join("roster.json");
+    let people = dir.path().join("people.csv");
+    std::fs::write(&people, "handle,display_name\nbob,Bob\n").unwrap();
+
+    cli(&roster)
+        .args(["import-people", "--csv"])
+        .arg(&people)
+        .assert()
+        .success();
+    cli(&roster)
+        .args([
+            "create-shift",
+            "--name",
+            "noel",
+            "--start",
+            "2025-12-26T08:00:00Z",
+            "--end",
+            "2025-12-26T20:00:00Z",
+        ])
+        .assert()
+        .success();
+    cli(&roster).arg("assign").assert().success();
+
+    cli(&roster)
+        .args(["vacation", "add", "--handle", "bob", "--from", "2025-12-24"])
+        .args(["--until", "2025-12-31", "--check"])
+        .assert()
+        .code(2)
+        .stdout(contains("conflict |"));
+    cli(&roster)
+        .args(["vacation", "list", "--handle", "bob"])
+        .assert()
+        .success()
+        .stdout(contains(
+            "bob | 0 | 2025-12-24T00:00:00+00:00 → 2026-01-01T00:00:00+00:00",
+        ));
+    cli(&roster)
+        .args(["vacation", "remove", "--handle", "bob", "--index", "0"])
+        .assert()
+        .success();
+    cli(&roster)
+        .args(["vacation", "list"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn reimporting_people_is_idempotent() {
+    let dir = tempfile::tempdir().unwrap();
+    let roster = dir.path().join("roster.json");
+    let people = dir.path().join("people.csv");
+    std::fs::write(&people, "handle,display_name\nalice,Alice\nbob,Bob\n").unwrap();
+
+    for _ in 0..2 {
+        cli(&roster)
+            .args(["import-people", "--csv"])
+            .arg(&people)
+            .assert()
+            .success();
+    }
+    std::fs::write(&people, "handle,display_name\nalice,Alice Dupont\n").unwrap();
+    cli(&roster)
+        .args(["import-people", "--dry-run", "--prune", "--csv"])
+        .arg(&people)
+        .assert()
+        .success()
+        .stdout(contains("~ alice (display_name)"))
+        .stdout(contains("- bob (deactivated)"));
+
+    let saved: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&roster).unwrap()).unwrap();
+    let people = saved["people"].as_array().unwrap();
+    assert_eq!(people.len(), 2);
+    assert_eq!(people[0]["display_name"], "Alice");
+}
+
+#[test]
+fn zone_given_to_assign_is_kept_for_later_covers() {
+    let dir = tempfile::tempdir().unwrap();
+    let roster = dir.path().join("roster.json");
+    let people = dir.path().join("people.csv");
+    std::fs::write(&people, "handle,display_name\nalice,Alice\nbob,Bob\n").unwrap();
+
+    cli(&roster)
+        .args(["import-people", "--csv"])
+        .arg(&people)
+        .assert()
+        .success();
+    // Lundi 09:00 -> 13:00 à Paris
+    cli(&roster)
+        .args([
+            "create-shift",
+            "--name",
+            "lundi",
+            "--start",
+            "2025-12-01T08:00:00Z",
+            "--end",
+            "2025-12-01T12:00:00Z",
+        ])
+        .assert()
+        .success();
+    cli(&roster)
+        .args(["assign", "--people", "alice", "--tz", "Europe/Paris"])
+        .assert()
+        .success();
+    cli(&roster)
+        .args(["availability", "add", "--handle", "bob"])
+        .args(["--rule", "never:mon@12:00-13:00"])
+        .assert()
+        .success();
+
+    let saved: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&roster).unwrap()).unwrap();
+    assert_eq!(saved["zone"], "Europe/Paris");
+    let shift_id = saved["shifts"][0]["id"].as_str().unwrap().to_string();
+    // 11:00 -> 13:00 à Paris empiète sur l'indisponibilité de Bob (en UTC, non)
+    cli(&roster)
+        .args(["cover", "--shift-id", &shift_id])
+        .args(["--from", "2025-12-01T10:00:00Z", "--with", "bob"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn rejected_row_does_not_deactivate_its_person_on_prune() {
+    let dir = tempfile::tempdir().unwrap();
+    let roster = dir.path().join("roster.json");
+    let people = dir.path().join("people.csv");
+    std::fs::write(
+        &people,
+        "handle,display_name\nalice,Alice\nbob,Bob\ncarol,Carol\n",
+    )
+    .unwrap();
+    cli(&roster)
+        .args(["import-people", "--csv"])
+        .arg(&people)
+        .assert()
+        .success();
+
+    // faute de frappe dans les congés de Bob, Carol retirée du fichier
+    std::fs::write(
+        &people,
+        "handle,display_name,on_vacation,vacations\nalice,Alice,,\nbob,Bob,,2025-13-01\n",
+    )
+    .unwrap();
+    cli(&roster)
+        .args(["import-people", "--skip-invalid", "--prune", "--csv"])
+        .arg(&people)
+        .assert()
+        .code(2)
+        .stdout(contains("- carol (deactivated)"))
+        .stdout(contains("- bob").not());
+
+    let saved: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&roster).unwrap()).unwrap();
+    let status = |handle: &str| {
+        let people = saved["people"].as_array().unwrap();
+        let p = people.iter().find(|p| p["handle"] == handle).unwrap();
+        p.get("status").cloned()
+    };
+    assert_eq!(status("bob"), None);
+    assert_eq!(status("carol"), Some("inactive".into()));
+}
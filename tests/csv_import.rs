@@ -0,0 +1,322 @@
+#![forbid(unsafe_code)]
+use astreinte::io::{self, ShiftImportOptions};
+use astreinte::{AssignOptions, Person, Role, Roster, Scheduler, ShiftId, Zone};
+use chrono::{Duration, TimeZone, Utc};
+use std::fs;
+
+fn roster() -> Roster {
+    Roster {
+        people: vec![Person::new("alice", "Alice"), Person::new("bob", "Bob")],
+        ..Roster::default()
+    }
+}
+
+#[test]
+fn semicolon_export_with_mapping_local_times_and_assignees() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("planning.csv");
+    fs::write(
+        &path,
+        "Libellé;Début;Fin;role;assigned_handle;timezone\n\
+         Nuit;06/01/2026 20:00;07/01/2026 08:00;secondary;alice;\n\
+         Jour NY;2026-01-07 09:00;2026-01-07 17:00;;bob;America/New_York\n",
+    )
+    .unwrap();
+    let r = roster();
+    let mut opts = ShiftImportOptions {
+        delimiter: b';',
+        zone: "Europe/Paris".parse::<Zone>().unwrap(),
+        ..ShiftImportOptions::default()
+    };
+    for (column, header) in [("name", "Libellé"), ("start", "Début"), ("end", "Fin")] {
+        opts.columns.insert(column.into(), header.into());
+    }
+    let shifts = io::import_shifts_csv_with(&path, &r, &opts).unwrap();
+
+    assert_eq!(shifts.len(), 2);
+    assert_eq!(shifts[0].name, "Nuit");
+    assert_eq!(
+        shifts[0].start,
+        Utc.with_ymd_and_hms(2026, 1, 6, 19, 0, 0).unwrap()
+    );
+    assert_eq!(shifts[0].role, Some(Role::Secondary));
+    assert_eq!(shifts[0].assigned, Some(r.people[0].id.clone()));
+    // la colonne timezone l'emporte sur le fuseau par défaut
+    assert_eq!(
+        shifts[1].start,
+        Utc.with_ymd_and_hms(2026, 1, 7, 14, 0, 0).unwrap()
+    );
+    assert_eq!(shifts[1].role, None);
+    assert_eq!(shifts[1].assigned, Some(r.people[1].id.clone()));
+}
+
+#[test]
+fn ids_legacy_positions_and_line_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let legacy = dir.path().join("legacy.csv");
+    fs::write(
+        &legacy,
+        "nom,debut,fin\nNuit,2025-10-01T20:00:00Z,2025-10-02T08:00:00Z\n",
+    )
+    .unwrap();
+    let shifts = io::import_shifts_csv(&legacy).unwrap();
+    assert_eq!(shifts[0].name, "Nuit");
+
+    let with_id = dir.path().join("ids.csv");
+    fs::write(
+        &with_id,
+        "id,name,start,end,assigned_handle\n\
+         s-1,Nuit,2025-10-01T20:00:00Z,2025-10-02T08:00:00Z,alice\n\
+         s-2,Nuit,2025-10-02T20:00:00Z,2025-10-03T08:00:00Z,zoe\n",
+    )
+    .unwrap();
+    let err = io::import_shifts_csv_with(&with_id, &roster(), &ShiftImportOptions::default())
+        .unwrap_err();
+    let msg = format!("{err:#}");
+    assert!(msg.contains("line 3") && msg.contains("zoe"), "{msg}");
+
+    fs::write(
+        &with_id,
+        "id,name,start,end\n\
+         s-1,Nuit,2025-10-01T20:00:00Z,2025-10-02T08:00:00Z\n\
+         s-1,Nuit,2025-10-02T20:00:00Z,2025-10-03T08:00:00Z\n",
+    )
+    .unwrap();
+    assert!(io::import_shifts_csv(&with_id).is_err());
+}
+
+/// Deux shifts (Alice, Bob), Bob remplace Alice deux heures au début du premier.
+fn scheduled() -> (Scheduler, Vec<ShiftId>) {
+    let mut s = Scheduler::new();
+    s.add_people(roster().people);
+    let alice = s.roster().people[0].id.clone();
+    let bob = s.roster().people[1].id.clone();
+    let t0 = Utc.with_ymd_and_hms(2025, 10, 1, 20, 0, 0).unwrap();
+    let ids: Vec<ShiftId> = [&alice, &bob]
+        .iter()
+        .enumerate()
+        .map(|(d, who)| {
+            let start = t0 + Duration::days(d as i64);
+            let id = s
+                .create_shift("Nuit", start, start + Duration::hours(12))
+                .unwrap();
+            s.roster_mut().find_shift_mut(&id).unwrap().assigned = Some((*who).clone());
+            id
+        })
+        .collect();
+    s.add_override(
+        &ids[0],
+        t0,
+        t0 + Duration::hours(2),
+        &bob,
+        AssignOptions {
+            min_rest_hours: 0,
+            ..AssignOptions::default()
+        },
+    )
+    .unwrap();
+    (s, ids)
+}
+
+#[test]
+fn export_reimports_unchanged_then_applies_spreadsheet_edits() {
+    let (mut s, ids) = scheduled();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("shifts.csv");
+    io::export_shifts_csv(&path, s.roster()).unwrap();
+    let before = serde_json::to_value(s.roster()).unwrap();
+
+    let opts = ShiftImportOptions::default();
+    let diff = io::import_shifts_into(&path, s.roster_mut(), &opts).unwrap();
+    assert!(diff.is_empty(), "{diff:?}");
+    assert_eq!(diff.unchanged, ids);
+    assert_eq!(serde_json::to_value(s.roster()).unwrap(), before);
+
+    // édition dans un tableur : renommage et réassignation du second shift
+    let csv = fs::read_to_string(&path).unwrap();
+    let edited: Vec<String> = csv
+        .lines()
+        .map(|l| {
+            if l.starts_with(ids[1].as_str()) {
+                l.replace("Nuit", "Nuit longue").replace(",bob,", ",alice,")
+            } else {
+                l.to_string()
+            }
+        })
+        .collect();
+    fs::write(&path, edited.join("\n")).unwrap();
+    let diff = io::import_shifts_into(&path, s.roster_mut(), &opts).unwrap();
+    assert_eq!(
+        diff.updated,
+        vec![(ids[1].clone(), vec!["name", "assigned"])]
+    );
+    let r = s.roster();
+    let second = r.find_shift(&ids[1]).unwrap();
+    assert_eq!(second.name, "Nuit longue");
+    assert_eq!(second.assigned, Some(r.people[0].id.clone()));
+    assert_eq!(r.overrides.len(), 1);
+    assert_eq!(r.shifts.len(), 2);
+}
+
+#[test]
+fn override_rows_follow_their_shift_only_when_requested() {
+    let (mut s, ids) = scheduled();
+    let dir = tempfile::tempdir().unwrap();
+    let plain = dir.path().join("plain.csv");
+    io::export_shifts_csv(&plain, s.roster()).unwrap();
+    let csv = fs::read_to_string(&plain).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "id,name,start,end,assigned_handle,parent_id,role");
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with(&format!(
+        "{},Nuit,2025-10-01T20:00:00+00:00,2025-10-02T08:00:00+00:00,alice,",
+        ids[0].as_str()
+    )));
+
+    let full = dir.path().join("full.csv");
+    io::export_shifts_csv_with(&full, s.roster(), true).unwrap();
+    let csv = fs::read_to_string(&full).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[2].starts_with(&format!(
+        "{},Nuit,2025-10-01T20:00:00+00:00,2025-10-01T22:00:00+00:00,bob,",
+        ids[0].as_str()
+    )));
+    assert!(lines[2].ends_with(",true"));
+
+    let opts = ShiftImportOptions::default();
+    let diff = io::import_shifts_into(&full, s.roster_mut(), &opts).unwrap();
+    assert!(diff.is_empty(), "{diff:?}");
+    // remplacement supprimé dans le tableur
+    let without: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|l| !l.ends_with(",true"))
+        .collect();
+    fs::write(&full, without.join("\n")).unwrap();
+    let diff = io::import_shifts_into(&full, s.roster_mut(), &opts).unwrap();
+    assert_eq!(diff.updated, vec![(ids[0].clone(), vec!["overrides"])]);
+    assert!(s.roster().overrides.is_empty());
+}
+
+#[test]
+fn moving_a_shift_away_from_its_overrides_is_rejected() {
+    let (mut s, ids) = scheduled();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("shifts.csv");
+    io::export_shifts_csv(&path, s.roster()).unwrap();
+    // Bob remplace Alice de 20:00 à 22:00 : le shift ne commence plus qu'à 23:00
+    let csv = fs::read_to_string(&path)
+        .unwrap()
+        .replace("2025-10-01T20:00:00+00:00", "2025-10-01T23:00:00+00:00");
+    fs::write(&path, csv).unwrap();
+    let before = serde_json::to_value(s.roster()).unwrap();
+
+    let opts = ShiftImportOptions::default();
+    let err = io::import_shifts_into(&path, s.roster_mut(), &opts).unwrap_err();
+    let errors = err.downcast_ref::<io::ImportErrors>().unwrap();
+    assert_eq!(errors.0.len(), 1);
+    assert_eq!(errors.0[0].line, 2);
+    assert!(errors.0[0].reason.contains("outside the new time range"));
+    assert_eq!(serde_json::to_value(s.roster()).unwrap(), before);
+    assert_eq!(s.roster().overrides[0].shift_id, ids[0]);
+}
+
+#[test]
+fn invalid_lines_are_all_reported_and_nothing_applied() {
+    let (mut s, ids) = scheduled();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("shifts.csv");
+    fs::write(
+        &path,
+        format!(
+            "id,name,start,end,assigned_handle\n\
+             {},Nuit,2025-10-01T20:00:00Z,2025-10-02T08:00:00Z,zoe\n\
+             ,Jour,2025-10-03T08:00:00Z,2025-10-03T20:00:00Z,bob\n\
+             ,Jour,2025-10-04T08:00:00Z,not a date,bob\n",
+            ids[0].as_str()
+        ),
+    )
+    .unwrap();
+    let before = serde_json::to_value(s.roster()).unwrap();
+    let err =
+        io::import_shifts_into(&path, s.roster_mut(), &ShiftImportOptions::default()).unwrap_err();
+    let errors = err.downcast_ref::<io::ImportErrors>().unwrap();
+    let lines: Vec<u64> = errors.0.iter().map(|e| e.line).collect();
+    assert_eq!(lines, vec![2, 4]);
+    assert!(errors.0[0].reason.contains("zoe"));
+    assert_eq!(serde_json::to_value(s.roster()).unwrap(), before);
+}
+
+#[test]
+fn people_validation_reports_every_row_with_its_column() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("people.csv");
+    fs::write(
+        &path,
+        "handle,display_name,on_vacation,vacations,availability\n\
+         alice,Alice,,,\n\
+         bob,,maybe,,\n\
+         alice,Alice bis,,,\n\
+         carol,Carol,,2025-12-20..2025-12-31;2025-12-24..2026-01-02,\n\
+         dan,Dan,,,\n",
+    )
+    .unwrap();
+    let err = io::import_people_csv(&path).unwrap_err();
+    let errors = err.downcast_ref::<io::ImportErrors>().unwrap();
+    let found: Vec<(u64, Option<&str>)> = errors
+        .0
+        .iter()
+        .map(|e| (e.line, e.column.as_deref()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (3, Some("display_name")),
+            (3, Some("on_vacation")),
+            (4, Some("handle")),
+            (5, Some("vacations")),
+        ]
+    );
+    assert!(errors.0[2].reason.contains("line 2"), "{}", errors.0[2]);
+
+    let (people, rejected) = io::import_people_csv_with(&path, true).unwrap();
+    let handles: Vec<&str> = people.iter().map(|p| p.handle.as_str()).collect();
+    assert_eq!(handles, vec!["alice", "dan"]);
+    assert_eq!(rejected.len(), 4);
+}
+
+#[test]
+fn skip_invalid_imports_valid_shifts_and_rejects_duplicates() {
+    let (mut s, _) = scheduled();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("shifts.csv");
+    fs::write(
+        &path,
+        "name,start,end,assigned_handle\n\
+         Nuit,2025-10-01T20:00:00Z,2025-10-02T08:00:00Z,alice\n\
+         Jour,2025-10-03T08:00:00Z,2025-10-03T20:00:00Z,bob\n\
+         Jour,2025-10-03T08:00:00Z,2025-10-03T20:00:00Z,alice\n\
+         Jour,2025-10-04T08:00:00Z,2025-10-04T20:00:00Z,zoe\n",
+    )
+    .unwrap();
+    let before = serde_json::to_value(s.roster()).unwrap();
+    let err =
+        io::import_shifts_into(&path, s.roster_mut(), &ShiftImportOptions::default()).unwrap_err();
+    let errors = err.downcast_ref::<io::ImportErrors>().unwrap();
+    let lines: Vec<u64> = errors.0.iter().map(|e| e.line).collect();
+    assert_eq!(lines, vec![2, 4, 5]);
+    assert!(errors.0[0].reason.contains("existing shift"));
+    assert!(errors.0[1].reason.contains("line 3"));
+    assert_eq!(errors.0[2].column.as_deref(), Some("assigned_handle"));
+    assert_eq!(serde_json::to_value(s.roster()).unwrap(), before);
+
+    let opts = ShiftImportOptions {
+        skip_invalid: true,
+        ..ShiftImportOptions::default()
+    };
+    let diff = io::import_shifts_into(&path, s.roster_mut(), &opts).unwrap();
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.rejected.len(), 3);
+    assert_eq!(s.roster().shifts.len(), 3);
+}
@@ -0,0 +1,92 @@
+#![forbid(unsafe_code)]
+use astreinte::escalation;
+use astreinte::{
+    AssignOptions, EscalationLevel, EscalationPolicy, EscalationTarget, Person, Role, Scheduler,
+    ShiftId, Team,
+};
+use chrono::{Duration, TimeZone, Utc};
+
+/// Équipe infra : Alice primaire, Bob secondaire, Carol responsable.
+fn setup() -> (Scheduler, [Person; 4], ShiftId) {
+    let mut s = Scheduler::new();
+    let people = [
+        Person::new("alice", "Alice"),
+        Person::new("bob", "Bob"),
+        Person::new("carol", "Carol"),
+        Person::new("dan", "Dan"),
+    ];
+    s.add_people(people.to_vec());
+    let t0 = Utc.with_ymd_and_hms(2025, 10, 1, 8, 0, 0).unwrap();
+    let mut make = |role: Role, who: &Person| {
+        let id = s
+            .create_shift("infra", t0, t0 + Duration::hours(24))
+            .unwrap();
+        let shift = s.roster_mut().find_shift_mut(&id).unwrap();
+        shift.team = Some("infra".into());
+        shift.role = Some(role);
+        shift.assigned = Some(who.id.clone());
+        id
+    };
+    let primary = make(Role::Primary, &people[0]);
+    make(Role::Secondary, &people[1]);
+    let level = |target, minutes| EscalationLevel {
+        target,
+        escalate_after_minutes: minutes,
+    };
+    s.roster_mut().teams.push(Team {
+        name: "infra".into(),
+        members: people[..2].iter().map(|p| p.id.clone()).collect(),
+        options: AssignOptions::default(),
+        escalation: Some(EscalationPolicy {
+            levels: vec![
+                level(EscalationTarget::OnCall(Role::Primary), 5),
+                level(EscalationTarget::OnCall(Role::Secondary), 10),
+                level(EscalationTarget::Person(people[2].id.clone()), 0),
+            ],
+        }),
+    });
+    (s, people, primary)
+}
+
+#[test]
+fn chain_resolves_on_call_people_and_cumulative_delays() {
+    let (s, [alice, bob, carol, _], primary) = setup();
+    let at = Utc.with_ymd_and_hms(2025, 10, 1, 12, 0, 0).unwrap();
+    let r = s.roster();
+    let chain = escalation::resolve(r, r.find_team("infra").unwrap(), at);
+
+    let people: Vec<_> = chain.iter().map(|c| c.person_id.clone().unwrap()).collect();
+    assert_eq!(people, vec![alice.id, bob.id, carol.id]);
+    let delays: Vec<_> = chain.iter().map(|c| c.notify_after_minutes).collect();
+    assert_eq!(delays, vec![0, 5, 15]);
+    assert_eq!(chain[0].shift_id, Some(primary));
+    assert_eq!(chain[2].shift_id, None);
+
+    let json = serde_json::to_value(&chain).unwrap();
+    assert_eq!(json[1]["handle"], "bob");
+    assert_eq!(json[2]["level"], 3);
+}
+
+#[test]
+fn chain_honours_overrides_and_reports_holes() {
+    let (mut s, [_, _, _, dan], primary) = setup();
+    let from = Utc.with_ymd_and_hms(2025, 10, 1, 14, 0, 0).unwrap();
+    s.add_override(
+        &primary,
+        from,
+        from + Duration::hours(2),
+        &dan.id,
+        AssignOptions::default(),
+    )
+    .unwrap();
+
+    let r = s.roster();
+    let team = r.find_team("infra").unwrap();
+    let chain = escalation::resolve(r, team, from + Duration::hours(1));
+    assert_eq!(chain[0].handle.as_deref(), Some("dan"));
+
+    // hors des shifts : seul le responsable reste joignable
+    let chain = escalation::resolve(r, team, from + Duration::days(2));
+    assert!(chain[0].person_id.is_none() && chain[1].person_id.is_none());
+    assert_eq!(chain[2].handle.as_deref(), Some("carol"));
+}
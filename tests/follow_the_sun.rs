@@ -0,0 +1,112 @@
+#![forbid(unsafe_code)]
+use astreinte::{
+    AssignOptions, AssignScope, CoverageKind, FollowTheSun, Person, Region, Scheduler, Team,
+};
+use chrono::{NaiveDate, TimeZone, Utc};
+
+/// EU 08:00-20:00 Paris puis US 11:00-23:00 Los Angeles : 24 h continues en hiver.
+fn setup() -> (Scheduler, Vec<Person>, Vec<Person>) {
+    let mut s = Scheduler::new();
+    let eu = vec![Person::new("alice", "Alice"), Person::new("bruno", "Bruno")];
+    let us = vec![Person::new("dana", "Dana"), Person::new("eric", "Eric")];
+    s.add_people(eu.iter().chain(&us).cloned().collect());
+    let team = |name: &str, people: &[Person]| Team {
+        name: name.into(),
+        members: people.iter().map(|p| p.id.clone()).collect(),
+        options: AssignOptions::default(),
+        escalation: None,
+    };
+    let roster = s.roster_mut();
+    roster.teams = vec![team("eu", &eu), team("us", &us)];
+    roster.follow_the_sun.push(FollowTheSun {
+        name: "sun".into(),
+        regions: vec![
+            "eu:Europe/Paris@08:00-20:00".parse().unwrap(),
+            "us:America/Los_Angeles@11:00-23:00".parse().unwrap(),
+        ],
+    });
+    (s, eu, us)
+}
+
+#[test]
+fn regions_chain_into_continuous_coverage() {
+    let (mut s, eu, us) = setup();
+    let day = NaiveDate::from_ymd_opt(2026, 1, 12).unwrap();
+    let created = s.generate_follow_the_sun("sun", day, 3).unwrap();
+    assert_eq!(created.len(), 6);
+    s.assign_follow_the_sun("sun", AssignScope::default())
+        .unwrap();
+
+    let r = s.roster();
+    for id in &created {
+        let shift = r.find_shift(id).unwrap();
+        let pool = if shift.team.as_deref() == Some("eu") {
+            &eu
+        } else {
+            &us
+        };
+        let holder = shift.assigned.as_ref().unwrap();
+        assert!(pool.iter().any(|p| &p.id == holder));
+    }
+    // passation EU -> US à 19:00 UTC, US -> EU à 07:00 UTC
+    let first = r.find_shift(&created[0]).unwrap();
+    assert_eq!(
+        first.start,
+        Utc.with_ymd_and_hms(2026, 1, 12, 7, 0, 0).unwrap()
+    );
+    assert_eq!(
+        first.end,
+        Utc.with_ymd_and_hms(2026, 1, 12, 19, 0, 0).unwrap()
+    );
+
+    let from = first.start;
+    let until = Utc.with_ymd_and_hms(2026, 1, 15, 7, 0, 0).unwrap();
+    // astreinte propre à l'équipe EU, hors rotation : pas de chevauchement
+    let extra = s
+        .create_shift("eu maintenance", from, from + chrono::Duration::hours(4))
+        .unwrap();
+    s.roster_mut().find_shift_mut(&extra).unwrap().team = Some("eu".into());
+    assert!(s
+        .follow_the_sun_coverage("sun", from, until)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn coverage_reports_gap_and_overlap_between_dst_changes() {
+    let (mut s, _, _) = setup();
+    // Les États-Unis sont passés à l'heure d'été, pas encore l'Europe.
+    let day = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+    s.generate_follow_the_sun("sun", day, 1).unwrap();
+
+    let from = Utc.with_ymd_and_hms(2026, 3, 10, 7, 0, 0).unwrap();
+    let until = Utc.with_ymd_and_hms(2026, 3, 11, 7, 0, 0).unwrap();
+    let issues = s.follow_the_sun_coverage("sun", from, until).unwrap();
+    assert_eq!(issues.len(), 2);
+    assert_eq!(issues[0].kind, CoverageKind::Overlap);
+    assert_eq!(
+        issues[0].start,
+        Utc.with_ymd_and_hms(2026, 3, 10, 18, 0, 0).unwrap()
+    );
+    assert_eq!(issues[1].kind, CoverageKind::Gap);
+    assert_eq!(
+        issues[1].start,
+        Utc.with_ymd_and_hms(2026, 3, 11, 6, 0, 0).unwrap()
+    );
+    assert_eq!(issues[1].end, until);
+}
+
+#[test]
+fn region_syntax_round_trips_and_is_checked() {
+    let region: Region = "eu:Europe/Paris@08:00-20:00".parse().unwrap();
+    assert_eq!(region.to_string(), "eu:Europe/Paris@08:00-20:00");
+    assert!("eu:Europe/Paris".parse::<Region>().is_err());
+    assert!("eu:Mars/Olympus@08:00-20:00".parse::<Region>().is_err());
+    assert!("eu:UTC@08:00-08:00".parse::<Region>().is_err());
+
+    let (mut s, _, _) = setup();
+    let day = NaiveDate::from_ymd_opt(2026, 1, 12).unwrap();
+    assert!(s.generate_follow_the_sun("moon", day, 1).is_err());
+    s.roster_mut().teams.clear();
+    assert!(s.generate_follow_the_sun("sun", day, 1).is_err());
+}
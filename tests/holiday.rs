@@ -0,0 +1,90 @@
+#![forbid(unsafe_code)]
+use astreinte::holiday::{easter_sunday, HolidayCalendar};
+use astreinte::{AssignOptions, Person, Scheduler, Zone};
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn french_calendar_includes_easter_based_holidays() {
+    assert_eq!(easter_sunday(2024), date(2024, 3, 31));
+    assert_eq!(easter_sunday(2025), date(2025, 4, 20));
+    assert_eq!(easter_sunday(2026), date(2026, 4, 5));
+
+    let cal = HolidayCalendar::french(2025);
+    assert_eq!(cal.iter().count(), 11);
+    assert_eq!(cal.name(date(2025, 4, 21)), Some("Lundi de Pâques"));
+    assert_eq!(cal.name(date(2025, 5, 29)), Some("Ascension"));
+    assert_eq!(cal.name(date(2025, 6, 9)), Some("Lundi de Pentecôte"));
+    assert_eq!(cal.name(date(2025, 7, 14)), Some("Fête nationale"));
+    assert!(!cal.contains(date(2025, 12, 24)));
+}
+
+fn two_people_with(cal: HolidayCalendar) -> (Scheduler, Person, Person) {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    s.add_people(vec![alice.clone(), bob.clone()]);
+    s.set_holidays(cal, Zone::Utc);
+    (s, alice, bob)
+}
+
+#[test]
+fn rotation_rule_avoids_same_holiday_two_years_in_a_row() {
+    let mut cal = HolidayCalendar::new();
+    cal.add_french_years(2024..=2025);
+    let (mut s, alice, bob) = two_people_with(cal);
+
+    let xmas24 = Utc.with_ymd_and_hms(2024, 12, 25, 8, 0, 0).unwrap();
+    let plain = Utc.with_ymd_and_hms(2025, 3, 4, 8, 0, 0).unwrap();
+    let xmas25 = Utc.with_ymd_and_hms(2025, 12, 25, 8, 0, 0).unwrap();
+    let a = s
+        .create_shift("noel24", xmas24, xmas24 + Duration::hours(12))
+        .unwrap();
+    s.create_shift("mardi", plain, plain + Duration::hours(12))
+        .unwrap();
+    let c = s
+        .create_shift("noel25", xmas25, xmas25 + Duration::hours(12))
+        .unwrap();
+
+    let opts = AssignOptions {
+        holiday_rotation: true,
+        ..AssignOptions::default()
+    };
+    s.assign_rotative(&[alice.clone(), bob.clone()], opts)
+        .unwrap();
+    let r = s.roster();
+    assert_eq!(r.find_shift(&a).unwrap().assigned.as_ref(), Some(&alice.id));
+    // Alice serait la suivante, mais elle a déjà eu Noël l'an dernier.
+    assert_eq!(r.find_shift(&c).unwrap().assigned.as_ref(), Some(&bob.id));
+}
+
+#[test]
+fn balancing_prefers_person_with_fewer_holidays() {
+    let (mut s, alice, bob) = two_people_with(HolidayCalendar::french(2025));
+
+    let jul = Utc.with_ymd_and_hms(2025, 7, 14, 8, 0, 0).unwrap();
+    let plain = Utc.with_ymd_and_hms(2025, 7, 22, 8, 0, 0).unwrap();
+    let aug = Utc.with_ymd_and_hms(2025, 8, 15, 8, 0, 0).unwrap();
+    let j = s
+        .create_shift("14 juillet", jul, jul + Duration::hours(12))
+        .unwrap();
+    s.create_shift("mardi", plain, plain + Duration::hours(12))
+        .unwrap();
+    let a = s
+        .create_shift("15 août", aug, aug + Duration::hours(12))
+        .unwrap();
+
+    let opts = AssignOptions {
+        balance_holidays: true,
+        ..AssignOptions::default()
+    };
+    s.assign_rotative(&[alice.clone(), bob.clone()], opts)
+        .unwrap();
+    let r = s.roster();
+    assert_eq!(r.find_shift(&j).unwrap().assigned.as_ref(), Some(&alice.id));
+    // Rotation : Alice ; équilibrage : Bob n'a encore aucun férié.
+    assert_eq!(r.find_shift(&a).unwrap().assigned.as_ref(), Some(&bob.id));
+}
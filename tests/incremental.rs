@@ -0,0 +1,86 @@
+#![forbid(unsafe_code)]
+use astreinte::{AssignOptions, AssignScope, Person, Scheduler, ShiftId};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+fn setup() -> (Scheduler, Person, Person, Vec<ShiftId>, DateTime<Utc>) {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    s.add_people(vec![alice.clone(), bob.clone()]);
+    let t0 = Utc.with_ymd_and_hms(2025, 10, 1, 8, 0, 0).unwrap();
+    let ids = (0..6)
+        .map(|d| {
+            let start = t0 + Duration::days(d);
+            s.create_shift(&format!("j{d}"), start, start + Duration::hours(12))
+                .unwrap()
+        })
+        .collect();
+    (s, alice, bob, ids, t0)
+}
+
+#[test]
+fn only_unassigned_keeps_existing_and_continues_rotation() {
+    let (mut s, alice, bob, ids, t0) = setup();
+    // Mois courant déjà assigné à la main (j0..j2), dernier shift tenu par Alice
+    for (id, who) in ids[..3].iter().zip([&bob, &bob, &alice]) {
+        s.roster_mut().find_shift_mut(id).unwrap().assigned = Some(who.id.clone());
+    }
+    let order: Vec<ShiftId> = s.roster().shifts.iter().map(|sh| sh.id.clone()).collect();
+
+    let scope = AssignScope {
+        only_unassigned: true,
+        now: Some(t0),
+        ..AssignScope::default()
+    };
+    let touched = s
+        .assign_incremental(
+            &[alice.clone(), bob.clone()],
+            AssignOptions::default(),
+            scope,
+        )
+        .unwrap();
+    assert_eq!(touched, ids[3..].to_vec());
+
+    let r = s.roster();
+    let who = |id: &ShiftId| r.find_shift(id).unwrap().assigned.clone().unwrap();
+    assert_eq!(who(&ids[0]), bob.id);
+    assert_eq!(who(&ids[1]), bob.id);
+    assert_eq!(who(&ids[2]), alice.id);
+    // reprise après Alice : Bob, Alice, Bob
+    assert_eq!(who(&ids[3]), bob.id);
+    assert_eq!(who(&ids[4]), alice.id);
+    assert_eq!(who(&ids[5]), bob.id);
+    let after: Vec<ShiftId> = r.shifts.iter().map(|sh| sh.id.clone()).collect();
+    assert_eq!(order, after);
+}
+
+#[test]
+fn window_mode_never_touches_past_shifts() {
+    let (mut s, alice, bob, ids, t0) = setup();
+    for id in &ids {
+        s.roster_mut().find_shift_mut(id).unwrap().assigned = Some(alice.id.clone());
+    }
+    let scope = AssignScope {
+        from: Some(t0),
+        until: Some(t0 + Duration::days(3)),
+        now: Some(t0 + Duration::days(1)),
+        ..AssignScope::default()
+    };
+    let touched = s
+        .assign_incremental(std::slice::from_ref(&bob), AssignOptions::default(), scope)
+        .unwrap();
+    assert_eq!(touched, ids[1..3].to_vec());
+    let r = s.roster();
+    assert_eq!(
+        r.find_shift(&ids[0]).unwrap().assigned.as_ref(),
+        Some(&alice.id)
+    );
+    assert_eq!(
+        r.find_shift(&ids[1]).unwrap().assigned.as_ref(),
+        Some(&bob.id)
+    );
+    assert_eq!(
+        r.find_shift(&ids[3]).unwrap().assigned.as_ref(),
+        Some(&alice.id)
+    );
+}
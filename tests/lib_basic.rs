@@ -1,6 +1,9 @@
 #![forbid(unsafe_code)]
+use astreinte::scheduler::SchedError;
 use astreinte::{prepare_reminder, TextReminder};
-use astreinte::{AssignOptions, Person, Scheduler, VacationPeriod};
+use astreinte::{
+    AssignOptions, AssignScope, ConflictKind, Person, Role, Scheduler, VacationPeriod,
+};
 use chrono::{TimeZone, Utc};
 
 #[test]
@@ -188,3 +191,393 @@ fn prepare_text_reminder_for_upcoming_shift() {
     assert!(reminder.content.contains("Alice"));
     assert!(reminder.content.contains("Journée"));
 }
+
+#[test]
+fn on_call_at_follows_cover_split_and_role() {
+    let mut scheduler = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    scheduler.add_people(vec![alice.clone(), bob.clone()]);
+
+    let start = Utc.with_ymd_and_hms(2025, 12, 26, 8, 0, 0).unwrap();
+    let mid = Utc.with_ymd_and_hms(2025, 12, 29, 8, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2026, 1, 2, 8, 0, 0).unwrap();
+
+    let shift_id = scheduler.create_shift("holiday", start, end).unwrap();
+    {
+        let shift = scheduler.roster_mut().find_shift_mut(&shift_id).unwrap();
+        shift.assigned = Some(alice.id.clone());
+        shift.role = Some(Role::Primary);
+    }
+    scheduler
+        .cover_shift(&shift_id, mid, &bob.id, AssignOptions::default())
+        .unwrap();
+
+    let roster = scheduler.roster();
+    let before = Utc.with_ymd_and_hms(2025, 12, 28, 12, 0, 0).unwrap();
+    let handles = |at| -> Vec<String> {
+        roster
+            .on_call_at(at, Some(&Role::Primary))
+            .iter()
+            .map(|p| p.handle.clone())
+            .collect()
+    };
+    assert_eq!(handles(before), vec!["alice"]);
+    assert_eq!(handles(mid), vec!["bob"]);
+    assert!(handles(end).is_empty());
+    assert!(roster.on_call_at(mid, Some(&Role::Secondary)).is_empty());
+}
+
+#[test]
+fn vacation_conflicts_lists_already_assigned_shifts() {
+    let mut scheduler = Scheduler::new();
+    let mut bob = Person::new("bob", "Bob");
+    let start = Utc.with_ymd_and_hms(2025, 12, 26, 8, 0, 0).unwrap();
+    let later = Utc.with_ymd_and_hms(2026, 1, 10, 8, 0, 0).unwrap();
+    bob.vacations =
+        vec![astreinte::io::parse_vacation_range("2025-12-24", Some("2025-12-31")).unwrap()];
+    assert_eq!(
+        bob.vacations[0].end,
+        Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap()
+    );
+    scheduler.add_people(vec![bob.clone()]);
+
+    let hit = scheduler
+        .create_shift("noel", start, start + chrono::Duration::hours(12))
+        .unwrap();
+    let miss = scheduler
+        .create_shift("janvier", later, later + chrono::Duration::hours(12))
+        .unwrap();
+    for id in [&hit, &miss] {
+        scheduler.roster_mut().find_shift_mut(id).unwrap().assigned = Some(bob.id.clone());
+    }
+
+    let impacted = scheduler.vacation_conflicts(&bob.id, AssignOptions::default());
+    assert_eq!(impacted, vec![hit]);
+}
+
+#[test]
+fn upsert_people_keeps_ids_and_prunes_missing() {
+    let mut roster = astreinte::Roster::default();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    roster.people = vec![alice.clone(), bob.clone()];
+
+    let mut renamed = Person::new("alice", "Alice Dupont");
+    renamed.on_vacation = true;
+    let diff = roster.upsert_people(vec![renamed, Person::new("carol", "Carol")], true);
+
+    assert_eq!(diff.added, vec!["carol"]);
+    assert_eq!(
+        diff.updated,
+        vec![("alice".to_string(), vec!["display_name", "on_vacation"])]
+    );
+    assert_eq!(diff.deactivated, vec!["bob"]);
+    assert_eq!(roster.people.len(), 3);
+    let a = roster.find_person_by_handle("alice").unwrap();
+    assert_eq!(a.id, alice.id);
+    assert_eq!(a.display_name, "Alice Dupont");
+    assert!(!roster.find_person_by_handle("bob").unwrap().is_active());
+
+    // Réimport identique : aucun changement, Bob réactivé s'il revient
+    let again = roster.upsert_people(vec![Person::new("bob", "Bob")], false);
+    assert_eq!(again.updated, vec![("bob".to_string(), vec!["status"])]);
+    assert!(roster.find_person_by_handle("bob").unwrap().is_active());
+}
+
+#[test]
+fn offboard_reassigns_future_shifts_and_keeps_history() {
+    let mut scheduler = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let carol = Person::new("carol", "Carol");
+    scheduler.add_people(vec![alice.clone(), carol.clone()]);
+
+    let t0 = Utc.with_ymd_and_hms(2025, 11, 3, 8, 0, 0).unwrap();
+    let day = chrono::Duration::days(1);
+    let twelve = chrono::Duration::hours(12);
+    let past = scheduler.create_shift("past", t0, t0 + twelve).unwrap();
+    let future = scheduler
+        .create_shift("future", t0 + day * 10, t0 + day * 10 + twelve)
+        .unwrap();
+    let other = scheduler
+        .create_shift("other", t0 + day * 2, t0 + day * 2 + twelve)
+        .unwrap();
+    for id in [&past, &future] {
+        scheduler.roster_mut().find_shift_mut(id).unwrap().assigned = Some(carol.id.clone());
+    }
+    scheduler
+        .roster_mut()
+        .find_shift_mut(&other)
+        .unwrap()
+        .assigned = Some(alice.id.clone());
+
+    // Carol remplace Alice deux heures sur un shift futur
+    let later = scheduler
+        .create_shift("later", t0 + day * 7, t0 + day * 7 + twelve)
+        .unwrap();
+    scheduler
+        .roster_mut()
+        .find_shift_mut(&later)
+        .unwrap()
+        .assigned = Some(alice.id.clone());
+    let replaced = t0 + day * 7 + chrono::Duration::hours(2);
+    scheduler
+        .add_override(
+            &later,
+            replaced,
+            replaced + chrono::Duration::hours(2),
+            &carol.id,
+            AssignOptions::default(),
+        )
+        .unwrap();
+
+    let from = t0 + day * 5;
+    let people = scheduler.roster().people.clone();
+    let report = scheduler
+        .offboard(&carol.id, from, &people, AssignOptions::default())
+        .unwrap();
+
+    assert_eq!(report.unassigned, vec![future.clone()]);
+    assert_eq!(report.reassigned, vec![(future.clone(), alice.id.clone())]);
+    let roster = scheduler.roster();
+    assert_eq!(
+        roster.find_shift(&past).unwrap().assigned.as_ref(),
+        Some(&carol.id)
+    );
+    assert_eq!(
+        roster.find_shift(&other).unwrap().assigned.as_ref(),
+        Some(&alice.id)
+    );
+    assert_eq!(report.removed_overrides.len(), 1);
+    assert!(roster.overrides.is_empty());
+    assert_eq!(
+        roster.on_call_at(replaced, None)[0].handle,
+        "alice".to_string()
+    );
+    let archived = roster.find_person_by_handle("carol").unwrap();
+    assert_eq!(archived.status, astreinte::PersonStatus::Archived);
+    assert_eq!(archived.active_until, Some(from));
+}
+
+#[test]
+fn locked_shift_survives_assignment_and_refuses_swap_unless_forced() {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    s.add_people(vec![alice.clone(), bob.clone()]);
+
+    let t0 = Utc.with_ymd_and_hms(2025, 12, 24, 8, 0, 0).unwrap();
+    let day = chrono::Duration::days(1);
+    let twelve = chrono::Duration::hours(12);
+    let christmas = s.create_shift("noel", t0 + day, t0 + day + twelve).unwrap();
+    s.create_shift("veille", t0, t0 + twelve).unwrap();
+    s.set_locked(&christmas, true, Some(&alice.id)).unwrap();
+
+    s.assign_rotative(&[bob.clone(), alice.clone()], AssignOptions::default())
+        .unwrap();
+    let shift = s.roster().find_shift(&christmas).unwrap();
+    assert!(shift.locked);
+    assert_eq!(shift.assigned.as_ref(), Some(&alice.id));
+
+    assert!(s
+        .swap(&christmas, &alice.id, &bob.id, AssignOptions::default())
+        .is_err());
+    assert!(s
+        .cover_shift(
+            &christmas,
+            t0 + day * 2 - twelve,
+            &bob.id,
+            AssignOptions::default()
+        )
+        .is_err());
+    s.swap_forced(&christmas, &alice.id, &bob.id, AssignOptions::default())
+        .unwrap();
+    assert_eq!(
+        s.roster().find_shift(&christmas).unwrap().assigned.as_ref(),
+        Some(&bob.id)
+    );
+}
+
+#[test]
+fn swap_rejects_rest_violation_unless_forced_and_audits_it() {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    s.add_people(vec![alice.clone(), bob.clone()]);
+
+    let t0 = Utc.with_ymd_and_hms(2025, 10, 6, 8, 0, 0).unwrap();
+    let h = chrono::Duration::hours;
+    let day = s.create_shift("jour", t0, t0 + h(12)).unwrap();
+    let night = s.create_shift("nuit", t0 + h(14), t0 + h(24)).unwrap();
+    s.roster_mut().find_shift_mut(&day).unwrap().assigned = Some(alice.id.clone());
+    s.roster_mut().find_shift_mut(&night).unwrap().assigned = Some(bob.id.clone());
+
+    // Alice n'aurait que 2 h de repos entre ses deux shifts
+    let err = s
+        .swap(&night, &alice.id, &bob.id, AssignOptions::default())
+        .unwrap_err();
+    assert_eq!(err.to_string(), "swap invalid: rest");
+    let relaxed = AssignOptions {
+        min_rest_hours: 2,
+        ..AssignOptions::default()
+    };
+    assert!(s.swap(&night, &alice.id, &bob.id, relaxed).is_ok());
+    s.swap(&night, &alice.id, &bob.id, relaxed).unwrap();
+
+    s.swap_forced(&night, &alice.id, &bob.id, AssignOptions::default())
+        .unwrap();
+    let entry = s.roster().audit.last().unwrap();
+    assert_eq!(entry.forced, vec!["rest".to_string()]);
+    assert_eq!(
+        s.roster().find_shift(&night).unwrap().assigned.as_ref(),
+        Some(&alice.id)
+    );
+}
+
+#[test]
+fn override_changes_who_is_on_call_without_splitting_shift() {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    s.add_people(vec![alice.clone(), bob.clone()]);
+
+    let t0 = Utc.with_ymd_and_hms(2025, 10, 6, 8, 0, 0).unwrap();
+    let h = chrono::Duration::hours;
+    let id = s.create_shift("lundi", t0, t0 + h(24)).unwrap();
+    s.roster_mut().find_shift_mut(&id).unwrap().assigned = Some(alice.id.clone());
+    s.add_override(&id, t0 + h(6), t0 + h(9), &bob.id, AssignOptions::default())
+        .unwrap();
+    // hors du shift : refusé
+    assert!(s
+        .add_override(
+            &id,
+            t0 + h(20),
+            t0 + h(30),
+            &bob.id,
+            AssignOptions::default()
+        )
+        .is_err());
+
+    let roster = s.roster();
+    let handle_at = |at| roster.on_call_at(at, None)[0].handle.clone();
+    assert_eq!(handle_at(t0 + h(5)), "alice");
+    assert_eq!(handle_at(t0 + h(6)), "bob");
+    assert_eq!(handle_at(t0 + h(9)), "alice");
+    let shift = roster.find_shift(&id).unwrap();
+    assert_eq!(roster.segments(shift).len(), 3);
+
+    let reminder = prepare_reminder(roster, "bob", 1, t0, &TextReminder).unwrap();
+    assert_eq!(reminder.shift_id, id.as_str());
+    assert_eq!(reminder.notice_at, t0 + h(6) - chrono::Duration::days(1));
+
+    assert!(matches!(
+        s.remove_override(&id, 1),
+        Err(SchedError::UnknownOverride(_, 1))
+    ));
+    assert_eq!(s.remove_override(&id, 0).unwrap().person, bob.id);
+    assert!(s.roster().overrides.is_empty());
+}
+
+#[test]
+fn override_holder_is_busy_for_assignment_and_conflicts() {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    s.add_people(vec![alice.clone(), bob.clone()]);
+
+    let t0 = Utc.with_ymd_and_hms(2025, 10, 6, 8, 0, 0).unwrap();
+    let h = chrono::Duration::hours;
+    let id = s.create_shift("lundi", t0, t0 + h(24)).unwrap();
+    s.roster_mut().find_shift_mut(&id).unwrap().assigned = Some(alice.id.clone());
+    s.add_override(&id, t0 + h(6), t0 + h(9), &bob.id, AssignOptions::default())
+        .unwrap();
+    assert!(s.detect_conflicts(AssignOptions::default()).is_empty());
+
+    // Bob tient déjà [6 h, 9 h) : le renfort de 7 h ne peut pas lui revenir
+    let extra = s.create_shift("renfort", t0 + h(7), t0 + h(8)).unwrap();
+    let scope = AssignScope {
+        only_unassigned: true,
+        ..AssignScope::default()
+    };
+    s.assign_incremental(std::slice::from_ref(&bob), AssignOptions::default(), scope)
+        .unwrap();
+    assert!(s.roster().find_shift(&extra).unwrap().assigned.is_none());
+
+    s.roster_mut().find_shift_mut(&extra).unwrap().assigned = Some(bob.id.clone());
+    let conflicts = s.detect_conflicts(AssignOptions::default());
+    assert!(conflicts
+        .iter()
+        .any(|c| c.person == bob.id && c.kind == ConflictKind::Overlap));
+    assert!(conflicts.iter().all(|c| c.person == bob.id));
+}
+
+#[test]
+fn uncover_and_merge_rejoin_cover_segments() {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    s.add_people(vec![alice.clone(), bob.clone()]);
+
+    let t0 = Utc.with_ymd_and_hms(2025, 10, 6, 8, 0, 0).unwrap();
+    let h = chrono::Duration::hours;
+    let id = s.create_shift("lundi", t0, t0 + h(24)).unwrap();
+    s.roster_mut().find_shift_mut(&id).unwrap().assigned = Some(alice.id.clone());
+
+    // cover puis uncover : Alice récupère tout le shift
+    let seg = s
+        .cover_shift(&id, t0 + h(12), &bob.id, AssignOptions::default())
+        .unwrap();
+    assert_eq!(
+        s.roster().find_shift(&seg).unwrap().parent.as_ref(),
+        Some(&id)
+    );
+    assert_eq!(s.uncover(&seg, AssignOptions::default()).unwrap(), id);
+    assert_eq!(s.roster().shifts.len(), 1);
+    let shift = s.roster().find_shift(&id).unwrap();
+    assert_eq!(
+        (shift.end, shift.assigned.as_ref()),
+        (t0 + h(24), Some(&alice.id))
+    );
+
+    // un remplacement après le point de reprise suit le segment ; celui qui
+    // chevauche ce point est coupé en deux
+    s.add_override(
+        &id,
+        t0 + h(10),
+        t0 + h(14),
+        &bob.id,
+        AssignOptions::default(),
+    )
+    .unwrap();
+    let carol = Person::new("carol", "Carol");
+    s.add_people(vec![carol.clone()]);
+    let seg = s
+        .cover_shift(&id, t0 + h(12), &carol.id, AssignOptions::default())
+        .unwrap();
+    let roster = s.roster();
+    let ranges: Vec<_> = roster
+        .overrides
+        .iter()
+        .map(|o| (o.shift_id.clone(), o.start, o.end))
+        .collect();
+    assert_eq!(
+        ranges,
+        vec![
+            (id.clone(), t0 + h(10), t0 + h(12)),
+            (seg.clone(), t0 + h(12), t0 + h(14)),
+        ]
+    );
+    assert_eq!(roster.on_call_at(t0 + h(13), None)[0].handle, "bob");
+    s.uncover(&seg, AssignOptions::default()).unwrap();
+    s.roster_mut().overrides.clear();
+
+    // cover, puis le segment revient à Alice par swap : merge recolle
+    let seg = s
+        .cover_shift(&id, t0 + h(12), &bob.id, AssignOptions::default())
+        .unwrap();
+    s.roster_mut().find_shift_mut(&seg).unwrap().assigned = Some(alice.id.clone());
+    assert_eq!(s.merge_shifts(&seg).unwrap(), 1);
+    assert_eq!(s.roster().shifts.len(), 1);
+    assert_eq!(s.roster().shifts[0].end, t0 + h(24));
+    assert!(s.uncover(&id, AssignOptions::default()).is_err());
+}
@@ -0,0 +1,101 @@
+#![forbid(unsafe_code)]
+use astreinte::{AssignOptions, AssignScope, ConflictKind, Person, Scheduler};
+use chrono::{Duration, TimeZone, Utc};
+
+#[test]
+fn monthly_cap_and_own_rest_steer_assignment() {
+    let mut s = Scheduler::new();
+    let mut junior = Person::new("junior", "Junior");
+    junior.limits.max_shifts_per_month = Some(1);
+    let senior = Person::new("senior", "Senior");
+    s.add_people(vec![junior.clone(), senior.clone()]);
+
+    let t0 = Utc.with_ymd_and_hms(2025, 10, 1, 8, 0, 0).unwrap();
+    for day in 0..4 {
+        let start = t0 + Duration::days(day * 2);
+        s.create_shift("jour", start, start + Duration::hours(12))
+            .unwrap();
+    }
+    s.assign_rotative(&[junior.clone(), senior.clone()], AssignOptions::default())
+        .unwrap();
+    let junior_count = s
+        .roster()
+        .shifts
+        .iter()
+        .filter(|sh| sh.assigned.as_ref() == Some(&junior.id))
+        .count();
+    assert_eq!(junior_count, 1);
+    assert!(s.detect_conflicts(AssignOptions::default()).is_empty());
+}
+
+#[test]
+fn manual_edits_breaking_limits_are_flagged() {
+    let mut s = Scheduler::new();
+    let mut bob = Person::new("bob", "Bob");
+    bob.limits.max_hours_per_7_days = Some(30);
+    bob.limits.max_weekends_per_quarter = Some(1);
+    bob.limits.min_rest_hours = Some(24);
+    s.add_people(vec![bob.clone()]);
+
+    // Deux samedis consécutifs de 20 h, séparés de 12 h de repos pour le second bloc
+    let sat1 = Utc.with_ymd_and_hms(2025, 10, 4, 6, 0, 0).unwrap();
+    let sat2 = sat1 + Duration::days(7);
+    let sun2 = sat2 + Duration::hours(32);
+    let ids = [
+        s.create_shift("sam1", sat1, sat1 + Duration::hours(20))
+            .unwrap(),
+        s.create_shift("sam2", sat2, sat2 + Duration::hours(20))
+            .unwrap(),
+        s.create_shift("dim2", sun2, sun2 + Duration::hours(12))
+            .unwrap(),
+    ];
+    for id in &ids {
+        s.roster_mut().find_shift_mut(id).unwrap().assigned = Some(bob.id.clone());
+    }
+
+    let kinds: Vec<ConflictKind> = s
+        .detect_conflicts(AssignOptions::default())
+        .into_iter()
+        .map(|c| c.kind)
+        .collect();
+    assert!(kinds.contains(&ConflictKind::WeeklyHoursLimit));
+    assert!(kinds.contains(&ConflictKind::QuarterlyWeekendLimit));
+    // 12 h entre sam2 et dim2 : OK au global (11 h) mais pas pour Bob (24 h)
+    assert!(kinds.contains(&ConflictKind::RestViolation));
+    assert!(!kinds.contains(&ConflictKind::MonthlyShiftLimit));
+}
+
+#[test]
+fn person_already_over_the_cap_is_not_chosen() {
+    let mut s = Scheduler::new();
+    let mut junior = Person::new("junior", "Junior");
+    junior.limits.max_shifts_per_month = Some(1);
+    let senior = Person::new("senior", "Senior");
+    s.add_people(vec![junior.clone(), senior.clone()]);
+
+    let t0 = Utc.with_ymd_and_hms(2025, 10, 1, 8, 0, 0).unwrap();
+    let ids: Vec<_> = (0..5)
+        .map(|day| {
+            let start = t0 + Duration::days(day * 3);
+            s.create_shift("jour", start, start + Duration::hours(12))
+                .unwrap()
+        })
+        .collect();
+    // Saisie manuelle : le junior a déjà deux shifts en octobre, et c'est son
+    // tour dans la rotation
+    for (id, who) in ids[..4].iter().zip([&junior, &junior, &senior, &senior]) {
+        s.roster_mut().find_shift_mut(id).unwrap().assigned = Some(who.id.clone());
+    }
+    let scope = AssignScope {
+        only_unassigned: true,
+        ..AssignScope::default()
+    };
+    s.assign_incremental(
+        &[junior.clone(), senior.clone()],
+        AssignOptions::default(),
+        scope,
+    )
+    .unwrap();
+    let last = s.roster().find_shift(&ids[4]).unwrap();
+    assert_eq!(last.assigned, Some(senior.id.clone()));
+}
@@ -0,0 +1,103 @@
+#![forbid(unsafe_code)]
+use astreinte::payroll::{compute, Intervention, PayrollOptions, RateTable};
+use astreinte::{AssignOptions, HolidayCalendar, Person, Scheduler, Zone};
+use chrono::{NaiveDate, TimeZone, Utc};
+
+#[test]
+fn payroll_splits_buckets_months_and_cover_segments() {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    s.add_people(vec![alice.clone(), bob.clone()]);
+
+    // Mercredi 31 décembre 12:00 -> vendredi 2 janvier 12:00 (Paris), 1er janvier férié
+    let paris: Zone = "Europe/Paris".parse().unwrap();
+    let start = Utc.with_ymd_and_hms(2025, 12, 31, 11, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2026, 1, 2, 11, 0, 0).unwrap();
+    let id = s.create_shift("fêtes", start, end).unwrap();
+    s.roster_mut().find_shift_mut(&id).unwrap().assigned = Some(alice.id.clone());
+
+    // Bob reprend le vendredi à partir de minuit (heure de Paris)
+    let cover = Utc.with_ymd_and_hms(2026, 1, 1, 23, 0, 0).unwrap();
+    s.cover_shift(&id, cover, &bob.id, AssignOptions::default())
+        .unwrap();
+
+    let mut holidays = HolidayCalendar::new();
+    holidays.insert(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(), "Jour de l'an");
+    let rates = RateTable {
+        weekday: 2.0,
+        weekend: 3.0,
+        holiday: 5.0,
+        intervention: 20.0,
+    };
+    let opts = PayrollOptions {
+        zone: paris,
+        holidays,
+        month: None,
+    };
+    let interventions = [Intervention {
+        handle: "bob".into(),
+        start: Utc.with_ymd_and_hms(2026, 1, 2, 3, 0, 0).unwrap(),
+        end: Utc.with_ymd_and_hms(2026, 1, 2, 4, 30, 0).unwrap(),
+    }];
+
+    let lines = compute(s.roster(), &rates, &opts, &interventions);
+    assert_eq!(lines.len(), 3);
+
+    let dec = &lines[0];
+    assert_eq!(
+        (dec.month.as_str(), dec.handle.as_str()),
+        ("2025-12", "alice")
+    );
+    assert_eq!(dec.weekday_hours, 12.0);
+    assert_eq!(dec.amount, 24.0);
+
+    let jan_alice = lines
+        .iter()
+        .find(|l| l.month == "2026-01" && l.handle == "alice")
+        .unwrap();
+    assert_eq!(jan_alice.holiday_hours, 24.0);
+    assert_eq!(jan_alice.weekday_hours, 0.0);
+
+    let jan_bob = lines.iter().find(|l| l.handle == "bob").unwrap();
+    assert_eq!(jan_bob.weekday_hours, 12.0);
+    assert_eq!(jan_bob.intervention_hours, 1.5);
+    assert_eq!(jan_bob.amount, 12.0 * 2.0 + 1.5 * 20.0);
+}
+
+#[test]
+fn payroll_pays_override_portion_to_replacement() {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    s.add_people(vec![alice.clone(), bob.clone()]);
+
+    // Lundi 24 h, Bob remplace Alice de 14:00 à 17:00
+    let start = Utc.with_ymd_and_hms(2025, 10, 6, 8, 0, 0).unwrap();
+    let id = s
+        .create_shift("lundi", start, start + chrono::Duration::hours(24))
+        .unwrap();
+    s.roster_mut().find_shift_mut(&id).unwrap().assigned = Some(alice.id.clone());
+    let from = Utc.with_ymd_and_hms(2025, 10, 6, 14, 0, 0).unwrap();
+    let until = Utc.with_ymd_and_hms(2025, 10, 6, 17, 0, 0).unwrap();
+    s.add_override(&id, from, until, &bob.id, AssignOptions::default())
+        .unwrap();
+    assert_eq!(s.roster().shifts.len(), 1);
+
+    let rates = RateTable {
+        weekday: 1.0,
+        weekend: 1.0,
+        holiday: 1.0,
+        intervention: 0.0,
+    };
+    let lines = compute(s.roster(), &rates, &PayrollOptions::default(), &[]);
+    let hours = |h: &str| {
+        lines
+            .iter()
+            .find(|l| l.handle == h)
+            .map(|l| l.weekday_hours)
+            .unwrap()
+    };
+    assert_eq!(hours("alice"), 21.0);
+    assert_eq!(hours("bob"), 3.0);
+}
@@ -0,0 +1,98 @@
+#![forbid(unsafe_code)]
+use astreinte::query::{render, OutputFormat, ShiftFilter, ShiftQuery, SortKey};
+use astreinte::{Person, Role, Scheduler, Zone};
+use chrono::{TimeZone, Utc};
+
+fn sample() -> (Scheduler, Person) {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    s.add_people(vec![alice.clone()]);
+
+    let d1 = Utc.with_ymd_and_hms(2025, 10, 2, 18, 0, 0).unwrap();
+    let d2 = Utc.with_ymd_and_hms(2025, 10, 1, 18, 0, 0).unwrap();
+    let late = s
+        .create_shift("Nuit", d1, d1 + chrono::Duration::hours(12))
+        .unwrap();
+    let early = s
+        .create_shift("Nuit", d2, d2 + chrono::Duration::hours(12))
+        .unwrap();
+    s.create_shift(
+        "Jour",
+        d1 + chrono::Duration::days(3),
+        d1 + chrono::Duration::days(4),
+    )
+    .unwrap();
+    let r = s.roster_mut();
+    r.find_shift_mut(&late).unwrap().assigned = Some(alice.id.clone());
+    r.find_shift_mut(&early).unwrap().role = Some(Role::Primary);
+    (s, alice)
+}
+
+#[test]
+fn filters_combine_and_sort_by_start() {
+    let (s, _) = sample();
+    let roster = s.roster();
+
+    let query = ShiftQuery {
+        filter: ShiftFilter {
+            name: Some("nuit".into()),
+            ..Default::default()
+        },
+        sort: Some(SortKey::Start),
+    };
+    let rows = query.run(roster);
+    assert_eq!(rows.len(), 2);
+    assert!(rows[0].shift.start < rows[1].shift.start);
+
+    let unassigned = ShiftQuery {
+        filter: ShiftFilter {
+            unassigned: true,
+            until: Some(Utc.with_ymd_and_hms(2025, 10, 3, 0, 0, 0).unwrap()),
+            ..Default::default()
+        },
+        sort: None,
+    };
+    let rows = unassigned.run(roster);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].shift.role, Some(Role::Primary));
+
+    let by_handle = ShiftQuery {
+        filter: ShiftFilter {
+            handle: Some("alice".into()),
+            ..Default::default()
+        },
+        sort: None,
+    };
+    let rows = by_handle.run(roster);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].handle(), Some("alice"));
+}
+
+#[test]
+fn render_formats_in_requested_zone() {
+    let (s, _) = sample();
+    let roster = s.roster();
+    let query = ShiftQuery {
+        filter: ShiftFilter {
+            handle: Some("alice".into()),
+            ..Default::default()
+        },
+        sort: None,
+    };
+    let rows = query.run(roster);
+    let paris: Zone = "Europe/Paris".parse().unwrap();
+
+    let table = render(&rows, OutputFormat::Table, Zone::Utc).unwrap();
+    assert!(table.contains("2025-10-02T18:00:00+00:00 → 2025-10-03T06:00:00+00:00 | alice"));
+
+    let csv = render(&rows, OutputFormat::Csv, paris).unwrap();
+    assert!(csv.starts_with("id,name,start,end,role,assigned_handle\n"));
+    assert!(csv.contains("2025-10-02T20:00:00+02:00"));
+
+    let md = render(&rows, OutputFormat::Markdown, paris).unwrap();
+    assert!(md.contains("| 2025-10-02 20:00 | 2025-10-03 08:00 |  | Alice |"));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&render(&rows, OutputFormat::Json, Zone::Utc).unwrap()).unwrap();
+    assert_eq!(json[0]["assigned_handle"], "alice");
+}
@@ -0,0 +1,197 @@
+#![forbid(unsafe_code)]
+use astreinte::io::{self, ScheduleFormat};
+use astreinte::{AssignOptions, Person, Roster, Scheduler, ShiftId};
+use chrono::{Duration, TimeZone, Utc};
+use std::fs;
+
+/// Deux jours assignés à Alice puis Bob, Carol remplace Alice 2 h le premier jour.
+fn sample() -> (Scheduler, Vec<ShiftId>) {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice Martin");
+    let bob = Person::new("bob", "Bob");
+    let carol = Person::new("carol", "Carol");
+    s.add_people(vec![alice.clone(), bob.clone(), carol.clone()]);
+    let t0 = Utc.with_ymd_and_hms(2025, 10, 1, 8, 0, 0).unwrap();
+    let ids: Vec<ShiftId> = [&alice, &bob]
+        .iter()
+        .enumerate()
+        .map(|(d, who)| {
+            let start = t0 + Duration::days(d as i64);
+            let id = s
+                .create_shift("jour", start, start + Duration::hours(24))
+                .unwrap();
+            s.roster_mut().find_shift_mut(&id).unwrap().assigned = Some(who.id.clone());
+            id
+        })
+        .collect();
+    s.add_override(
+        &ids[0],
+        t0 + Duration::hours(2),
+        t0 + Duration::hours(4),
+        &carol.id,
+        AssignOptions::default(),
+    )
+    .unwrap();
+    (s, ids)
+}
+
+fn round_trip(roster: &Roster, format: ScheduleFormat) -> Roster {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("schedule.json");
+    io::export_schedule_json(&path, roster, format, "astreinte").unwrap();
+    io::import_schedule_json(&path, format).unwrap()
+}
+
+#[test]
+fn pagerduty_and_opsgenie_round_trip_into_existing_roster() {
+    for format in [ScheduleFormat::PagerDuty, ScheduleFormat::Opsgenie] {
+        let (mut s, ids) = sample();
+        let mut imported = round_trip(s.roster(), format);
+        assert_eq!(imported.shifts.len(), 2);
+        assert_eq!(imported.overrides.len(), 1);
+        // changement fait dans l'outil externe : Bob prend aussi le premier jour
+        let bob = imported.find_person_by_handle("bob").unwrap().id.clone();
+        imported.shifts[0].assigned = Some(bob);
+
+        // réimport dans le roster d'origine : personnes et shifts reconnus
+        let summary = io::merge_schedule(s.roster_mut(), imported);
+        assert!(summary.people_added.is_empty(), "{format:?}");
+        assert_eq!((summary.shifts, summary.matched), (0, 2));
+        assert_eq!(summary.reassigned, vec![ids[0].clone()]);
+        assert_eq!(summary.overrides, 0);
+
+        let r = s.roster();
+        assert_eq!((r.shifts.len(), r.overrides.len()), (2, 1));
+        let first = r.find_shift(&ids[0]).unwrap();
+        assert_eq!(first.name, "jour");
+        assert_eq!(
+            first.assigned,
+            Some(r.find_person_by_handle("bob").unwrap().id.clone())
+        );
+        assert_eq!(
+            r.holder_at(first, first.start + Duration::hours(3)),
+            Some(&r.find_person_by_handle("carol").unwrap().id)
+        );
+    }
+}
+
+#[test]
+fn importing_the_same_schedule_twice_adds_nothing_the_second_time() {
+    let (s, _) = sample();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("pd.json");
+    io::export_schedule_json(&path, s.roster(), ScheduleFormat::PagerDuty, "astreinte").unwrap();
+
+    let mut roster = Roster::default();
+    let first = io::merge_schedule(
+        &mut roster,
+        io::import_schedule_json(&path, ScheduleFormat::PagerDuty).unwrap(),
+    );
+    assert_eq!(
+        (first.people_added.len(), first.shifts, first.overrides),
+        (3, 2, 1)
+    );
+    let before = serde_json::to_value(&roster).unwrap();
+
+    let second = io::merge_schedule(
+        &mut roster,
+        io::import_schedule_json(&path, ScheduleFormat::PagerDuty).unwrap(),
+    );
+    assert!(second.people_added.is_empty());
+    assert_eq!((second.shifts, second.matched, second.overrides), (0, 2, 0));
+    assert!(second.reassigned.is_empty());
+    assert_eq!(serde_json::to_value(&roster).unwrap(), before);
+}
+
+#[test]
+fn imports_third_party_shapes() {
+    let dir = tempfile::tempdir().unwrap();
+    let pd = dir.path().join("pd.json");
+    fs::write(
+        &pd,
+        r#"{
+  "schedule": {
+    "name": "Ops",
+    "time_zone": "Europe/Paris",
+    "schedule_layers": [{
+      "name": "Layer 1",
+      "start": "2025-10-01T10:00:00+02:00",
+      "rendered_schedule_entries": [
+        {"start": "2025-10-01T10:00:00+02:00", "end": "2025-10-02T10:00:00+02:00",
+         "user": {"id": "PXPGF42", "type": "user_reference", "summary": "Jane Doe"}}
+      ]
+    }]
+  }
+}"#,
+    )
+    .unwrap();
+    let roster = io::import_pagerduty_json(&pd).unwrap();
+    assert_eq!(roster.people[0].handle, "jane.doe");
+    assert_eq!(roster.people[0].id.as_str(), "PXPGF42");
+    assert_eq!(
+        roster.shifts[0].start,
+        Utc.with_ymd_and_hms(2025, 10, 1, 8, 0, 0).unwrap()
+    );
+
+    let og = dir.path().join("og.json");
+    fs::write(
+        &og,
+        r#"{
+  "data": {"name": "Ops", "timezone": "UTC", "enabled": true, "rotations": []},
+  "timeline": {"baseTimeline": {"rotations": [{"name": "Nights", "periods": [
+    {"startDate": "2025-10-01T20:00:00Z", "endDate": "2025-10-02T08:00:00Z", "type": "default",
+     "recipient": {"type": "user", "name": "jane@example.com"}},
+    {"startDate": "2025-10-02T20:00:00Z", "endDate": "2025-10-03T08:00:00Z", "type": "default",
+     "recipient": {"type": "none"}}
+  ]}]}}
+}"#,
+    )
+    .unwrap();
+    let roster = io::import_opsgenie_json(&og).unwrap();
+    assert_eq!(roster.people.len(), 1);
+    assert_eq!(roster.people[0].handle, "jane");
+    assert_eq!(roster.shifts.len(), 2);
+    assert!(roster.shifts[1].assigned.is_none());
+
+    assert!("splunk".parse::<ScheduleFormat>().is_err());
+}
+
+#[test]
+fn opsgenie_override_stays_on_its_rotation_and_gaps_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let og = dir.path().join("og.json");
+    let doc = |override_start: &str, override_end: &str| {
+        format!(
+            r#"{{
+  "data": {{"name": "Ops", "timezone": "UTC", "enabled": true, "rotations": []}},
+  "timeline": {{"baseTimeline": {{"rotations": [
+    {{"name": "eu", "periods": [
+      {{"startDate": "2025-10-01T08:00:00Z", "endDate": "2025-10-01T20:00:00Z", "type": "default",
+        "recipient": {{"type": "user", "name": "alice@example.com"}}}}]}},
+    {{"name": "us", "periods": [
+      {{"startDate": "2025-10-01T08:00:00Z", "endDate": "2025-10-01T20:00:00Z", "type": "default",
+        "recipient": {{"type": "user", "name": "bob@example.com"}}}}]}}
+  ]}}}},
+  "overrides": [{{"alias": "dentist", "user": {{"type": "user", "name": "carol@example.com"}},
+    "startDate": "{override_start}", "endDate": "{override_end}",
+    "rotations": [{{"name": "eu"}}]}}]
+}}"#
+        )
+    };
+    fs::write(&og, doc("2025-10-01T10:00:00Z", "2025-10-01T12:00:00Z")).unwrap();
+    let roster = io::import_opsgenie_json(&og).unwrap();
+    assert_eq!(roster.overrides.len(), 1);
+    let eu = roster.shifts.iter().find(|s| s.name == "eu").unwrap();
+    assert_eq!(roster.overrides[0].shift_id, eu.id);
+    let us = roster.shifts.iter().find(|s| s.name == "us").unwrap();
+    let at = Utc.with_ymd_and_hms(2025, 10, 1, 11, 0, 0).unwrap();
+    assert_eq!(
+        roster.holder_at(us, at),
+        Some(&roster.find_person_by_handle("bob").unwrap().id)
+    );
+
+    // remplacement dans un trou du planning : signalé, pas ignoré
+    fs::write(&og, doc("2025-10-02T10:00:00Z", "2025-10-02T12:00:00Z")).unwrap();
+    let err = io::import_opsgenie_json(&og).unwrap_err();
+    assert!(format!("{err:#}").contains("covers no shift"), "{err:#}");
+}
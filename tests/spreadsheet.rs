@@ -0,0 +1,191 @@
+#![forbid(unsafe_code)]
+use astreinte::io;
+use astreinte::{AssignOptions, HolidayCalendar, Person, Role, Scheduler, Zone};
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+
+/// Alice primaire du vendredi 19:00 au lundi 07:00 (Paris), Bob secondaire le
+/// samedi, Carol remplace Alice le dimanche midi.
+fn weekend() -> Scheduler {
+    let mut s = Scheduler::new();
+    s.add_people(vec![
+        Person::new("alice", "Alice Martin"),
+        Person::new("bob", "Bob"),
+        Person::new("carol", "Carol"),
+    ]);
+    let ids: Vec<_> = s.roster().people.iter().map(|p| p.id.clone()).collect();
+    let friday = Utc.with_ymd_and_hms(2025, 10, 31, 18, 0, 0).unwrap();
+    let primary = s
+        .create_shift("week-end", friday, friday + Duration::hours(61))
+        .unwrap();
+    s.roster_mut().find_shift_mut(&primary).unwrap().assigned = Some(ids[0].clone());
+    let saturday = Utc.with_ymd_and_hms(2025, 11, 1, 8, 0, 0).unwrap();
+    let secondary = s
+        .create_shift("renfort", saturday, saturday + Duration::hours(10))
+        .unwrap();
+    let shift = s.roster_mut().find_shift_mut(&secondary).unwrap();
+    shift.role = Some(Role::Secondary);
+    shift.assigned = Some(ids[1].clone());
+    let noon = Utc.with_ymd_and_hms(2025, 11, 2, 11, 0, 0).unwrap();
+    s.add_override(
+        &primary,
+        noon,
+        noon + Duration::hours(2),
+        &ids[2],
+        AssignOptions {
+            min_rest_hours: 0,
+            ..AssignOptions::default()
+        },
+    )
+    .unwrap();
+    s
+}
+
+#[test]
+fn planning_grid_has_one_row_per_day_and_one_column_per_role() {
+    let s = weekend();
+    let zone: Zone = "Europe/Paris".parse().unwrap();
+    let from = NaiveDate::from_ymd_opt(2025, 10, 31).unwrap();
+    let grid = io::planning_grid(
+        s.roster(),
+        from,
+        4,
+        zone,
+        &HolidayCalendar::french(2025),
+        None,
+    );
+
+    assert_eq!(grid.roles, vec![Role::Primary, Role::Secondary]);
+    let cells: Vec<_> = grid.days.iter().map(|d| d.cells.clone()).collect();
+    let names = |list: &[&str]| list.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    assert_eq!(cells[0], vec![names(&["Alice Martin"]), vec![]]);
+    assert_eq!(cells[1], vec![names(&["Alice Martin"]), names(&["Bob"])]);
+    assert_eq!(cells[2], vec![names(&["Alice Martin", "Carol"]), vec![]]);
+    assert_eq!(cells[3], vec![names(&["Alice Martin"]), vec![]]);
+
+    let flags: Vec<_> = grid.days.iter().map(|d| d.weekend).collect();
+    assert_eq!(flags, vec![false, true, true, false]);
+    // 1er novembre : Toussaint
+    assert!(grid.days[1].holiday.is_some());
+    assert!(grid.days[0].holiday.is_none());
+}
+
+#[cfg(feature = "spreadsheet")]
+mod workbook {
+    use super::*;
+    use astreinte::io::ShiftImportOptions;
+    use calamine::{open_workbook_auto, Reader};
+    use rust_xlsxwriter::{ExcelDateTime, Format, Workbook};
+
+    #[test]
+    fn xlsx_people_and_shifts_go_through_csv_validation() {
+        let dir = tempfile::tempdir().unwrap();
+        let people = dir.path().join("people.xlsx");
+        let mut book = Workbook::new();
+        let sheet = book.add_worksheet();
+        let date = Format::new().set_num_format("yyyy-mm-dd");
+        for (col, title) in ["handle", "display_name", "on_vacation", "vacations"]
+            .iter()
+            .enumerate()
+        {
+            sheet.write_string(0, col as u16, *title).unwrap();
+        }
+        sheet.write_string(1, 0, "alice").unwrap();
+        sheet.write_string(1, 1, "Alice").unwrap();
+        sheet.write_number(1, 2, 0).unwrap();
+        let christmas = ExcelDateTime::from_ymd(2025, 12, 25).unwrap();
+        sheet
+            .write_datetime_with_format(1, 3, &christmas, &date)
+            .unwrap();
+        sheet.write_string(3, 0, "bob").unwrap();
+        sheet.write_string(3, 1, "Bob").unwrap();
+        sheet.write_string(4, 0, "alice").unwrap();
+        sheet.write_string(4, 1, "Alice bis").unwrap();
+        book.save(&people).unwrap();
+
+        let err = io::import_people_csv(&people).unwrap_err();
+        let errors = err.downcast_ref::<io::ImportErrors>().unwrap();
+        assert_eq!(errors.0.len(), 1);
+        assert_eq!(errors.0[0].line, 5);
+        let (imported, _) = io::import_people_csv_with(&people, true).unwrap();
+        assert_eq!(imported.len(), 2);
+        assert_eq!(
+            imported[0].vacations[0].start,
+            Utc.with_ymd_and_hms(2025, 12, 25, 0, 0, 0).unwrap()
+        );
+
+        let shifts = dir.path().join("shifts.xlsx");
+        let mut book = Workbook::new();
+        let sheet = book.add_worksheet();
+        let stamp = Format::new().set_num_format("yyyy-mm-dd hh:mm");
+        for (col, title) in ["Libellé", "Début", "Fin", "assigned_handle"]
+            .iter()
+            .enumerate()
+        {
+            sheet.write_string(0, col as u16, *title).unwrap();
+        }
+        sheet.write_string(1, 0, "Nuit").unwrap();
+        let start = ExcelDateTime::parse_from_str("2025-12-01 20:00").unwrap();
+        let end = ExcelDateTime::parse_from_str("2025-12-02").unwrap();
+        sheet
+            .write_datetime_with_format(1, 1, &start, &stamp)
+            .unwrap();
+        sheet
+            .write_datetime_with_format(1, 2, &end, &stamp)
+            .unwrap();
+        sheet.write_string(1, 3, "alice").unwrap();
+        book.save(&shifts).unwrap();
+
+        let mut s = Scheduler::new();
+        s.add_people(imported);
+        let mut opts = ShiftImportOptions {
+            zone: "Europe/Paris".parse().unwrap(),
+            ..ShiftImportOptions::default()
+        };
+        for (column, header) in [("name", "Libellé"), ("start", "Début"), ("end", "Fin")] {
+            opts.columns.insert(column.into(), header.into());
+        }
+        let diff = io::import_shifts_into(&shifts, s.roster_mut(), &opts).unwrap();
+        assert_eq!(diff.added.len(), 1);
+        let shift = &s.roster().shifts[0];
+        assert_eq!(
+            (shift.start, shift.end),
+            (
+                Utc.with_ymd_and_hms(2025, 12, 1, 19, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 12, 1, 23, 0, 0).unwrap()
+            )
+        );
+        assert_eq!(shift.assigned, Some(s.roster().people[0].id.clone()));
+    }
+
+    #[test]
+    fn planning_xlsx_lists_display_names_per_role() {
+        let s = weekend();
+        let from = NaiveDate::from_ymd_opt(2025, 10, 31).unwrap();
+        let grid = io::planning_grid(
+            s.roster(),
+            from,
+            4,
+            "Europe/Paris".parse().unwrap(),
+            &HolidayCalendar::french(2025),
+            None,
+        );
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("planning.xlsx");
+        io::export_planning_xlsx(&path, &grid).unwrap();
+
+        let mut book = open_workbook_auto(&path).unwrap();
+        let range = book.worksheet_range("Planning").unwrap();
+        let rows: Vec<Vec<String>> = range
+            .rows()
+            .map(|r| r.iter().map(|c| c.to_string()).collect())
+            .collect();
+        assert_eq!(
+            rows[0],
+            vec!["date", "day", "primary", "secondary", "holiday"]
+        );
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[2][..4], ["2025-11-01", "Sat", "Alice Martin", "Bob"]);
+        assert_eq!(rows[3][2], "Alice Martin, Carol");
+        assert!(!rows[2][4].is_empty());
+    }
+}
@@ -0,0 +1,134 @@
+#![forbid(unsafe_code)]
+use astreinte::stats::{compute, Period, StatsOptions};
+use astreinte::{AssignOptions, HolidayCalendar, Person, PersonStatus, Scheduler, Team, Zone};
+use chrono::{NaiveDate, TimeZone, Utc};
+
+#[test]
+fn stats_count_hours_nights_weekends_and_covers() {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    s.add_people(vec![alice.clone(), bob.clone()]);
+
+    // Vendredi 20:00 -> samedi 08:00 UTC (12 h, dont 8 h de nuit 22:00-06:00)
+    let fri = Utc.with_ymd_and_hms(2025, 11, 14, 20, 0, 0).unwrap();
+    let night = s
+        .create_shift("nuit", fri, fri + chrono::Duration::hours(12))
+        .unwrap();
+    // Mardi 11 novembre (férié) 08:00 -> 20:00
+    let tue = Utc.with_ymd_and_hms(2025, 11, 11, 8, 0, 0).unwrap();
+    let day = s
+        .create_shift("jour", tue, tue + chrono::Duration::hours(12))
+        .unwrap();
+    {
+        let r = s.roster_mut();
+        r.find_shift_mut(&night).unwrap().assigned = Some(alice.id.clone());
+        r.find_shift_mut(&day).unwrap().assigned = Some(alice.id.clone());
+    }
+    // Bob reprend la fin de la nuit à 02:00
+    let at = Utc.with_ymd_and_hms(2025, 11, 15, 2, 0, 0).unwrap();
+    s.cover_shift(&night, at, &bob.id, AssignOptions::default())
+        .unwrap();
+
+    let mut holidays = HolidayCalendar::new();
+    holidays.insert(NaiveDate::from_ymd_opt(2025, 11, 11).unwrap(), "Armistice");
+    let opts = StatsOptions {
+        period: Period::Month,
+        zone: Zone::Utc,
+        holidays,
+        ..Default::default()
+    };
+    let rows = compute(s.roster(), &opts);
+    assert_eq!(rows.len(), 2);
+    let a = rows.iter().find(|r| r.handle == "alice").unwrap();
+    let b = rows.iter().find(|r| r.handle == "bob").unwrap();
+
+    assert_eq!(a.period, "2025-11");
+    assert_eq!(a.shifts, 2);
+    assert_eq!(a.hours, 18.0);
+    assert_eq!(a.night_hours, 4.0);
+    assert_eq!(a.holiday_shifts, 1);
+    assert_eq!(a.weekend_shifts, 1);
+    assert_eq!(a.covers_received, 1);
+
+    assert_eq!(b.hours, 6.0);
+    assert_eq!(b.night_hours, 4.0);
+    assert_eq!(b.weekend_shifts, 1);
+    assert_eq!(b.covers_given, 1);
+
+    assert_eq!(a.hours_deviation, 6.0);
+    assert_eq!(b.hours_deviation, -6.0);
+}
+
+#[test]
+fn override_hours_go_to_the_holder_not_the_assignee() {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    s.add_people(vec![alice.clone(), bob.clone()]);
+
+    // Mercredi 08:00 -> 20:00, Bob remplace Alice de 12:00 à 15:00
+    let wed = Utc.with_ymd_and_hms(2025, 11, 12, 8, 0, 0).unwrap();
+    let day = s
+        .create_shift("jour", wed, wed + chrono::Duration::hours(12))
+        .unwrap();
+    s.roster_mut().find_shift_mut(&day).unwrap().assigned = Some(alice.id.clone());
+    s.add_override(
+        &day,
+        wed + chrono::Duration::hours(4),
+        wed + chrono::Duration::hours(7),
+        &bob.id,
+        AssignOptions::default(),
+    )
+    .unwrap();
+
+    let rows = compute(s.roster(), &StatsOptions::default());
+    let a = rows.iter().find(|r| r.handle == "alice").unwrap();
+    let b = rows.iter().find(|r| r.handle == "bob").unwrap();
+    assert_eq!((a.shifts, a.hours), (1, 9.0));
+    assert_eq!((b.shifts, b.hours), (1, 3.0));
+    assert_eq!((a.covers_received, b.covers_given), (1, 1));
+}
+
+#[test]
+fn team_mean_ignores_archived_and_not_yet_active_members() {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    let carol = Person::new("carol", "Carol");
+    let mut dave = Person::new("dave", "Dave");
+    dave.status = PersonStatus::Archived;
+    let mut erin = Person::new("erin", "Erin");
+    erin.active_from = Some(Utc.with_ymd_and_hms(2025, 12, 1, 0, 0, 0).unwrap());
+    let people = [alice, bob, carol, dave, erin];
+    s.add_people(people.to_vec());
+
+    let mut day = NaiveDate::from_ymd_opt(2025, 11, 10).unwrap();
+    for (who, hours) in [(&people[0], 12), (&people[1], 4), (&people[2], 10)] {
+        let start = Utc.from_utc_datetime(&day.and_hms_opt(8, 0, 0).unwrap());
+        let id = s
+            .create_shift("jour", start, start + chrono::Duration::hours(hours))
+            .unwrap();
+        s.roster_mut().find_shift_mut(&id).unwrap().assigned = Some(who.id.clone());
+        day = day.succ_opt().unwrap();
+    }
+    let team = |name: &str, members: &[&Person]| Team {
+        name: name.into(),
+        members: members.iter().map(|p| p.id.clone()).collect(),
+        options: AssignOptions::default(),
+        escalation: None,
+    };
+    let ops = team("ops", &[&people[0], &people[1], &people[3], &people[4]]);
+    let db = team("db", &[&people[2]]);
+    s.roster_mut().teams.extend([ops, db]);
+
+    let rows = compute(s.roster(), &StatsOptions::default());
+    let handles: Vec<&str> = rows.iter().map(|r| r.handle.as_str()).collect();
+    assert_eq!(handles, ["alice", "bob", "carol"]);
+    let by = |h: &str| rows.iter().find(|r| r.handle == h).unwrap();
+    assert_eq!(by("alice").team.as_deref(), Some("ops"));
+    assert_eq!(by("alice").hours_deviation, 4.0);
+    assert_eq!(by("bob").hours_deviation, -4.0);
+    assert_eq!(by("carol").hours_deviation, 0.0);
+    assert_eq!(by("carol").shifts_deviation, 0.0);
+}
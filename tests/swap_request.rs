@@ -0,0 +1,122 @@
+#![forbid(unsafe_code)]
+use astreinte::{AssignOptions, Person, Scheduler, ShiftId, SwapStatus, VacationPeriod};
+use chrono::{Duration, TimeZone, Utc};
+
+/// Bob tient le samedi, Alice le mercredi et le jeudi (dans le futur).
+fn setup() -> (Scheduler, Person, Person, ShiftId, ShiftId, ShiftId) {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    s.add_people(vec![alice.clone(), bob.clone()]);
+    let t0 = Utc.with_ymd_and_hms(2099, 6, 3, 8, 0, 0).unwrap();
+    let twelve = Duration::hours(12);
+    let mut create = |name: &str, days: i64, who: &Person| {
+        let start = t0 + Duration::days(days);
+        let id = s.create_shift(name, start, start + twelve).unwrap();
+        s.roster_mut().find_shift_mut(&id).unwrap().assigned = Some(who.id.clone());
+        id
+    };
+    let wed = create("mercredi", 0, &alice);
+    let thu = create("jeudi", 1, &alice);
+    let sat = create("samedi", 3, &bob);
+    (s, alice, bob, wed, thu, sat)
+}
+
+#[test]
+fn accepted_one_for_n_trade_moves_all_shifts() {
+    let (mut s, alice, bob, wed, thu, sat) = setup();
+    let id = s
+        .propose_swap(
+            &bob.id,
+            &alice.id,
+            vec![sat.clone()],
+            vec![wed.clone(), thu.clone()],
+            None,
+        )
+        .unwrap();
+    s.accept_swap_request(&id, AssignOptions::default())
+        .unwrap();
+
+    let r = s.roster();
+    assert_eq!(
+        r.find_shift(&sat).unwrap().assigned.as_ref(),
+        Some(&alice.id)
+    );
+    assert_eq!(r.find_shift(&wed).unwrap().assigned.as_ref(), Some(&bob.id));
+    assert_eq!(r.find_shift(&thu).unwrap().assigned.as_ref(), Some(&bob.id));
+    assert_eq!(
+        r.find_swap_request(&id).unwrap().status,
+        SwapStatus::Accepted
+    );
+    assert_eq!(r.audit.len(), 3);
+    assert!(s
+        .accept_swap_request(&id, AssignOptions::default())
+        .is_err());
+}
+
+#[test]
+fn failing_trade_is_rolled_back_entirely() {
+    let (mut s, alice, bob, wed, thu, sat) = setup();
+    let id = s
+        .propose_swap(
+            &bob.id,
+            &alice.id,
+            vec![sat.clone()],
+            vec![wed.clone(), thu.clone()],
+            None,
+        )
+        .unwrap();
+    // Bob part en congés le jeudi : le troisième échange échoue
+    let thu_start = s.roster().find_shift(&thu).unwrap().start;
+    s.roster_mut().people[1].vacations.push(VacationPeriod {
+        start: thu_start,
+        end: thu_start + Duration::hours(12),
+    });
+    assert!(s
+        .accept_swap_request(&id, AssignOptions::default())
+        .is_err());
+
+    let r = s.roster();
+    assert_eq!(r.find_shift(&sat).unwrap().assigned.as_ref(), Some(&bob.id));
+    assert_eq!(
+        r.find_shift(&wed).unwrap().assigned.as_ref(),
+        Some(&alice.id)
+    );
+    assert!(r.audit.is_empty());
+    assert_eq!(
+        r.find_swap_request(&id).unwrap().status,
+        SwapStatus::Pending
+    );
+}
+
+#[test]
+fn rejected_and_expired_requests_cannot_be_accepted() {
+    let (mut s, alice, bob, wed, _thu, sat) = setup();
+    let rejected = s
+        .propose_swap(
+            &bob.id,
+            &alice.id,
+            vec![sat.clone()],
+            vec![wed.clone()],
+            None,
+        )
+        .unwrap();
+    s.reject_swap_request(&rejected).unwrap();
+    assert!(s
+        .accept_swap_request(&rejected, AssignOptions::default())
+        .is_err());
+
+    let soon = Utc::now() + Duration::hours(1);
+    let expiring = s
+        .propose_swap(&bob.id, &alice.id, vec![sat.clone()], vec![], Some(soon))
+        .unwrap();
+    assert_eq!(s.expire_swap_requests(soon), vec![expiring.clone()]);
+    assert!(s
+        .accept_swap_request(&expiring, AssignOptions::default())
+        .is_err());
+
+    // on ne peut pas proposer un shift qu'on ne tient pas
+    assert!(s
+        .propose_swap(&bob.id, &alice.id, vec![wed], vec![], None)
+        .is_err());
+}
@@ -0,0 +1,100 @@
+#![forbid(unsafe_code)]
+use astreinte::{AssignOptions, AssignScope, ConflictKind, Person, Scheduler, ShiftId, Team};
+use chrono::{Duration, TimeZone, Utc};
+
+/// Deux équipes (infra, db) avec des shifts simultanés ; Carol est dans les deux.
+fn setup() -> (Scheduler, [Person; 3], Vec<ShiftId>, Vec<ShiftId>) {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    let carol = Person::new("carol", "Carol");
+    s.add_people(vec![alice.clone(), bob.clone(), carol.clone()]);
+    let t0 = Utc.with_ymd_and_hms(2025, 10, 1, 8, 0, 0).unwrap();
+    let mut make = |team: &str| -> Vec<ShiftId> {
+        (0..4)
+            .map(|d| {
+                let start = t0 + Duration::days(d);
+                let id = s
+                    .create_shift(&format!("{team}{d}"), start, start + Duration::hours(12))
+                    .unwrap();
+                s.roster_mut().find_shift_mut(&id).unwrap().team = Some(team.into());
+                id
+            })
+            .collect()
+    };
+    let infra = make("infra");
+    let db = make("db");
+    s.roster_mut().teams = vec![
+        Team {
+            name: "infra".into(),
+            members: vec![carol.id.clone(), alice.id.clone()],
+            options: AssignOptions::default(),
+            escalation: None,
+        },
+        Team {
+            name: "db".into(),
+            members: vec![carol.id.clone(), bob.id.clone()],
+            options: AssignOptions {
+                max_consecutive_shifts: 1,
+                ..AssignOptions::default()
+            },
+            escalation: None,
+        },
+    ];
+    (s, [alice, bob, carol], infra, db)
+}
+
+#[test]
+fn shared_member_never_on_call_for_two_teams_at_once() {
+    let (mut s, [alice, bob, carol], infra, db) = setup();
+    s.assign_team("infra", AssignScope::default()).unwrap();
+    s.assign_team("db", AssignScope::default()).unwrap();
+
+    let r = s.roster();
+    let who = |id: &ShiftId| r.find_shift(id).unwrap().assigned.clone();
+    for (a, b) in infra.iter().zip(&db) {
+        assert!(who(a).is_some() && who(b).is_some());
+        assert_ne!(who(a), who(b));
+    }
+    // seuls les membres de chaque équipe sont retenus
+    assert!(infra.iter().all(|id| who(id) != Some(bob.id.clone())));
+    assert!(db.iter().all(|id| who(id) != Some(alice.id.clone())));
+    assert!(infra.iter().any(|id| who(id) == Some(carol.id.clone())));
+    assert!(s.detect_conflicts(AssignOptions::default()).is_empty());
+}
+
+#[test]
+fn team_options_and_scope_are_per_rotation() {
+    let (mut s, [_, bob, carol], infra, db) = setup();
+    s.assign_team("db", AssignScope::default()).unwrap();
+    let r = s.roster();
+    let who = |id: &ShiftId| r.find_shift(id).unwrap().assigned.clone().unwrap();
+    // max 1 shift consécutif pour db : alternance stricte
+    assert_eq!(
+        db.iter().map(who).collect::<Vec<_>>(),
+        vec![
+            carol.id.clone(),
+            bob.id.clone(),
+            carol.id.clone(),
+            bob.id.clone()
+        ]
+    );
+    // les shifts infra ne sont pas touchés
+    assert!(infra
+        .iter()
+        .all(|id| r.find_shift(id).unwrap().assigned.is_none()));
+
+    assert!(s.assign_team("unknown", AssignScope::default()).is_err());
+}
+
+#[test]
+fn cross_team_overlap_is_reported() {
+    let (mut s, [_, _, carol], infra, db) = setup();
+    for id in [&infra[0], &db[0]] {
+        s.roster_mut().find_shift_mut(id).unwrap().assigned = Some(carol.id.clone());
+    }
+    let conflicts = s.detect_conflicts(AssignOptions::default());
+    assert!(conflicts.iter().any(|c| c.kind == ConflictKind::Overlap
+        && c.person == carol.id
+        && [&c.shift_a, &c.shift_b] == [&infra[0], &db[0]]));
+}
@@ -0,0 +1,129 @@
+#![forbid(unsafe_code)]
+use astreinte::scheduler::SchedError;
+use astreinte::{AssignOptions, Person, Role, Scheduler, ShiftId, SwapStatus, VacationPeriod};
+use chrono::{Duration, TimeZone, Utc};
+
+/// Alice tient le primaire, Bob le secondaire, sur le même créneau.
+fn setup() -> (Scheduler, Person, Person, ShiftId, ShiftId) {
+    let mut s = Scheduler::new();
+    let alice = Person::new("alice", "Alice");
+    let bob = Person::new("bob", "Bob");
+    s.add_people(vec![alice.clone(), bob.clone()]);
+    let t0 = Utc.with_ymd_and_hms(2025, 10, 6, 8, 0, 0).unwrap();
+    let t1 = t0 + Duration::hours(12);
+    let primary = s.create_shift("lundi", t0, t1).unwrap();
+    let secondary = s.create_shift("lundi", t0, t1).unwrap();
+    for (id, role, who) in [
+        (&primary, Role::Primary, &alice),
+        (&secondary, Role::Secondary, &bob),
+    ] {
+        let shift = s.roster_mut().find_shift_mut(id).unwrap();
+        shift.role = Some(role);
+        shift.assigned = Some(who.id.clone());
+    }
+    (s, alice, bob, primary, secondary)
+}
+
+#[test]
+fn exchange_valid_only_as_a_whole_is_accepted() {
+    let (mut s, alice, bob, primary, secondary) = setup();
+    // seul, le premier swap laisse Bob sur deux shifts simultanés
+    assert!(s
+        .swap(&primary, &alice.id, &bob.id, AssignOptions::default())
+        .is_err());
+
+    s.transaction(AssignOptions::default(), |tx| {
+        tx.swap(&primary, &alice.id, &bob.id)?;
+        tx.swap(&secondary, &alice.id, &bob.id)
+    })
+    .unwrap();
+    let r = s.roster();
+    assert_eq!(
+        r.find_shift(&primary).unwrap().assigned.as_ref(),
+        Some(&bob.id)
+    );
+    assert_eq!(
+        r.find_shift(&secondary).unwrap().assigned.as_ref(),
+        Some(&alice.id)
+    );
+    assert_eq!(r.audit.len(), 2);
+}
+
+#[test]
+fn invalid_final_state_rolls_everything_back() {
+    let (mut s, alice, bob, primary, _) = setup();
+    let t0 = s.roster().find_shift(&primary).unwrap().start;
+    s.roster_mut().people[1].vacations.push(VacationPeriod {
+        start: t0 + Duration::days(3),
+        end: t0 + Duration::days(4),
+    });
+
+    let err = s
+        .transaction(AssignOptions::default(), |tx| {
+            tx.delete_shift(&primary)?;
+            let extra = tx.create_shift(
+                "jeudi",
+                t0 + Duration::days(3),
+                t0 + Duration::days(3) + Duration::hours(12),
+            )?;
+            tx.reassign(&extra, Some(&bob.id))
+        })
+        .unwrap_err();
+    assert!(matches!(err, SchedError::TransactionInvalid(_)));
+
+    let r = s.roster();
+    assert_eq!(r.shifts.len(), 2);
+    assert_eq!(
+        r.find_shift(&primary).unwrap().assigned.as_ref(),
+        Some(&alice.id)
+    );
+    assert!(r.audit.is_empty());
+}
+
+#[test]
+fn error_inside_closure_rolls_back() {
+    let (mut s, alice, bob, primary, _) = setup();
+    let res: Result<(), SchedError> = s.transaction(AssignOptions::default(), |tx| {
+        tx.reassign(&primary, None)?;
+        tx.swap(&ShiftId::new("missing"), &alice.id, &bob.id)
+    });
+    assert!(res.is_err());
+    assert_eq!(
+        s.roster().find_shift(&primary).unwrap().assigned.as_ref(),
+        Some(&alice.id)
+    );
+}
+
+#[test]
+fn deleting_a_shift_drops_its_overrides_and_expires_requests() {
+    let (mut s, alice, bob, primary, secondary) = setup();
+    let carol = Person::new("carol", "Carol");
+    s.add_people(vec![carol.clone()]);
+    let t0 = s.roster().find_shift(&primary).unwrap().start;
+    s.add_override(
+        &primary,
+        t0 + Duration::hours(2),
+        t0 + Duration::hours(4),
+        &carol.id,
+        AssignOptions::default(),
+    )
+    .unwrap();
+    let request = s
+        .propose_swap(
+            &alice.id,
+            &bob.id,
+            vec![primary.clone()],
+            vec![secondary.clone()],
+            None,
+        )
+        .unwrap();
+
+    let removed = s
+        .transaction(AssignOptions::default(), |tx| tx.delete_shift(&primary))
+        .unwrap();
+    assert_eq!(removed.id, primary);
+    let r = s.roster();
+    assert!(r.overrides.is_empty());
+    assert_eq!(r.swap_requests[0].id, request);
+    assert_eq!(r.swap_requests[0].status, SwapStatus::Expired);
+}